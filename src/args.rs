@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+/// Command line options for the game.
+#[derive(Default)]
+pub struct Args {
+    /// Record the run to this asciicast file from the start.
+    pub record: Option<PathBuf>,
+    /// Log filter spec like `info,splat::game=debug`, overriding `SPLAT_LOG`.
+    pub log: Option<String>,
+    /// Log file, overriding `SPLAT_LOG_FILE`.
+    pub log_file: Option<PathBuf>,
+    /// Key bindings file, overriding `SPLAT_BINDINGS`.
+    pub bindings: Option<PathBuf>,
+    /// Record the gameplay input to this replay file from the start.
+    pub record_input: Option<PathBuf>,
+    /// Play back a replay file recorded with `--record-input`.
+    pub replay: Option<PathBuf>,
+    /// Print the key bindings and any problems with them, then exit.
+    pub list_bindings: bool,
+}
+
+impl Args {
+    pub fn parse() -> std::io::Result<Self> {
+        let mut args = Self::default();
+        let mut iter = std::env::args().skip(1);
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--record" => args.record = Some(Self::value(&arg, iter.next())?.into()),
+                "--log" => args.log = Some(Self::value(&arg, iter.next())?),
+                "--log-file" => args.log_file = Some(Self::value(&arg, iter.next())?.into()),
+                "--bindings" => args.bindings = Some(Self::value(&arg, iter.next())?.into()),
+                "--record-input" => {
+                    args.record_input = Some(Self::value(&arg, iter.next())?.into())
+                }
+                "--replay" => args.replay = Some(Self::value(&arg, iter.next())?.into()),
+                "--list-bindings" => args.list_bindings = true,
+                _ => return Err(Self::invalid(format!("unknown argument {arg:?}"))),
+            }
+        }
+
+        Ok(args)
+    }
+
+    fn value(flag: &str, value: Option<String>) -> std::io::Result<String> {
+        value.ok_or_else(|| Self::invalid(format!("{flag} needs a value")))
+    }
+
+    fn invalid(msg: String) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, msg)
+    }
+}
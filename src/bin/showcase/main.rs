@@ -1,74 +1,103 @@
 use crossterm::terminal;
 
-use splat::engine::{Button, Camera, Drawable, Input, Logger, Pos, Renderer, ScreenPos};
-use splat::game::{Bear, Border, UPDATE_RATE};
+use splat::engine::{
+    BorderStyle, Button, Corner, Drawable, GameLoop, Input, LogConfig, LogConsole, Logger,
+    Placement, Pos, Renderer, Split, TerminalSession, Viewport, Viewports,
+};
+use splat::game::{Bear, MIN_COLUMNS, MIN_ROWS, RENDER_RATE, UPDATE_INTERVAL};
 use ui_button::UiButtons;
 
 mod ui_button;
 
 fn main() -> std::io::Result<()> {
     let size = terminal::window_size()?;
-    let width = size.columns;
-    let height = size.rows - 10;
-
-    let logger = Logger::setup().unwrap();
-    let mut renderer = Renderer::new(width, height, Some(logger))?;
-
-    // leave room for border and status bar
-    let mut camera = Camera {
-        pos: Pos::ZERO,
-        frame_pos: ScreenPos::new(1, 1),
-        width: width - 2,
-        height: height - 3,
-    };
-
-    let ui_camera = Camera {
-        pos: Pos::ZERO,
-        frame_pos: ScreenPos::ZERO,
-        width,
-        height,
-    };
+    let (mut width, mut height) = (size.columns, size.rows);
+
+    let logger = Logger::setup_with(LogConfig::from_env()?)?;
+    let session = TerminalSession::start()?;
+    let mut renderer = Renderer::new(width, height)?;
+
+    let mut viewports = Viewports::new(Split::Horizontal);
+    let world_view = viewports.add(Viewport::new(Placement::Fill).border(BorderStyle::Single));
+    let bear_view = viewports.add(
+        Viewport::new(Placement::Fill)
+            .border(BorderStyle::Rounded)
+            .title("bear cam"),
+    );
+    let ui_view = viewports.add(
+        Viewport::new(Placement::Inset {
+            corner: Corner::TopLeft,
+            width: 22,
+            height: 12,
+        })
+        .transparent(),
+    );
+    viewports.layout(width, height);
 
     let mut input = Input::new()?;
 
-    let border = Border;
+    let mut console = LogConsole::new(logger);
 
     let mut ui_buttons = UiButtons::new();
 
-    let mut bear = Bear::new(Pos::new(-50.0, 20.0));
-
-    // use spin_sleep since native sleep is often too slow / low res
-    let mut loop_helper = spin_sleep::LoopHelper::builder()
-        .report_interval_s(1.0)
-        .build_with_target_rate(UPDATE_RATE);
-    let mut actual_rate = 0.0;
-
-    loop {
-        loop_helper.loop_start();
-
-        if let Some(rate) = loop_helper.report_rate() {
-            actual_rate = rate;
+    let bear_pos = Pos::new(-50.0, 20.0);
+    let mut bear = Bear::new(bear_pos);
+
+    let mut game_loop = GameLoop::new(UPDATE_INTERVAL, RENDER_RATE);
+
+    'game: loop {
+        game_loop.tick();
+
+        while let Some(clock) = game_loop.step() {
+            input.update(viewports.camera(world_view))?;
+            if session.terminated() || input.pressed_this_frame(Button::Quit) {
+                break 'game;
+            }
+            if input.mouse_moved() {
+                splat::dbg!(input.mouse_pos);
+            }
+
+            if let Some(size) = input.resized() {
+                (width, height) = size;
+                renderer.resize(width, height);
+                viewports.layout(width, height);
+            }
+
+            if width < MIN_COLUMNS || height < MIN_ROWS {
+                game_loop.skip_step();
+                continue;
+            }
+
+            console.update(&input, height);
+            bear.update(&clock);
+            ui_buttons.update(&input, viewports.camera_mut(world_view));
         }
-        splat::dbg!(actual_rate);
 
-        input.update(&camera)?;
-        if input.pressed_this_frame(Button::Quit) {
-            break;
-        }
-        splat::dbg!(input.mouse_pos);
+        if game_loop.render().is_some() {
+            renderer.clear();
 
-        bear.update();
-        ui_buttons.update(&input, &mut camera);
+            if width < MIN_COLUMNS || height < MIN_ROWS {
+                renderer.paint_notice(&format!(
+                    "please enlarge the terminal to at least {MIN_COLUMNS}x{MIN_ROWS}"
+                ));
+            } else {
+                // the bear sprite is about 26 cells square
+                viewports
+                    .camera_mut(bear_view)
+                    .center_on(bear_pos + Pos::new(13.0, 26.0));
 
-        renderer.clear();
+                let world: [&dyn Drawable; 1] = [&bear];
+                viewports.draw(world_view, &world, &mut renderer);
+                viewports.draw(bear_view, &world, &mut renderer);
+                viewports.draw(ui_view, &[&ui_buttons], &mut renderer);
 
-        border.draw(&camera, &mut renderer);
-        bear.draw(&camera, &mut renderer);
-        ui_buttons.draw(&ui_camera, &mut renderer);
+                renderer.draw(&console, viewports.camera(world_view));
+            }
 
-        renderer.render()?;
+            renderer.render()?;
+        }
 
-        loop_helper.loop_sleep();
+        game_loop.sleep();
     }
 
     Ok(())
@@ -1,4 +1,7 @@
-use splat::engine::{Button, Camera, Drawable, Input, ScreenCoord, ScreenPos, Sprite};
+use splat::engine::{
+    Align, BorderStyle, Button, Camera, Dimension, Drawable, Input, Layer, Panel, Pos, Rect,
+    ScreenCoord, ScreenPos,
+};
 
 enum State {
     Normal,
@@ -9,26 +12,29 @@ enum State {
 
 struct UiButton {
     screen_pos: ScreenPos,
-    sprite: Sprite,
-    hover_sprite: Sprite,
-    press_sprite: Sprite,
+    label: char,
     state: State,
 }
 
 impl UiButton {
-    fn update(&mut self, input: &Input) {
-        let width = self.sprite.len() as ScreenCoord;
-        if width == 0 {
-            return;
-        }
+    const WIDTH: Dimension = 5;
+    const HEIGHT: Dimension = 3;
 
-        let height = self.sprite[0].len() as ScreenCoord / 2;
-        if height == 0 {
-            return;
+    fn new(screen_pos: ScreenPos, label: char) -> Self {
+        Self {
+            screen_pos,
+            label,
+            state: State::Normal,
         }
+    }
 
+    fn update(&mut self, input: &Input) {
         let offset = input.mouse_screen_pos - self.screen_pos;
-        if 0 <= offset.x && offset.x <= width && 0 <= offset.y && offset.y <= height {
+        if 0 <= offset.x
+            && offset.x < Self::WIDTH as ScreenCoord
+            && 0 <= offset.y
+            && offset.y < Self::HEIGHT as ScreenCoord
+        {
             if input.pressed_this_frame(Button::LeftMouse) {
                 self.state = State::ClickedThisFrame;
             } else if input.pressed(Button::LeftMouse) {
@@ -48,12 +54,27 @@ impl UiButton {
 
 impl Drawable for UiButton {
     fn draw(&self, camera: &splat::engine::Camera, renderer: &mut splat::engine::Renderer) {
-        let sprite = match self.state {
-            State::Normal => &self.sprite,
-            State::Hovered => &self.hover_sprite,
-            State::ClickedThisFrame | State::Pressed => &self.press_sprite,
+        let border = match self.state {
+            State::Normal => BorderStyle::Single,
+            State::Hovered => BorderStyle::Heavy,
+            State::ClickedThisFrame | State::Pressed => BorderStyle::Double,
         };
-        camera.paint_sprite(sprite, self.screen_pos.into(), renderer);
+
+        let screen_pos = self.screen_pos + camera.frame_pos - ScreenPos::from(camera.pos);
+        if screen_pos.x < 0 || screen_pos.y < 0 {
+            return;
+        }
+
+        let rect = Rect::new(
+            screen_pos.x as Dimension,
+            screen_pos.y as Dimension,
+            Self::WIDTH,
+            Self::HEIGHT,
+        );
+        Panel::new(rect, border).paint_with(renderer, |renderer, inner| {
+            let center = inner.x + inner.width / 2;
+            renderer.print(center, inner.y, Align::Center, &self.label.to_string());
+        });
     }
 }
 
@@ -74,107 +95,21 @@ impl Drawable for UiButtons {
         self.down_button.draw(camera, renderer);
         self.msg_button.draw(camera, renderer);
     }
+
+    fn layer(&self) -> Layer {
+        Layer::Hud
+    }
 }
 
 impl UiButtons {
     pub fn new() -> Self {
         let base_pos = ScreenPos::new(10, 5);
         Self {
-            left_button: UiButton {
-                screen_pos: base_pos + ScreenPos::new(-6, 0),
-                sprite: vec![
-                    vec!['┌', '─', '─', '─', '┐'],
-                    vec!['│', ' ', '◀', ' ', '│'],
-                    vec!['└', '─', '─', '─', '┘'],
-                ],
-                hover_sprite: vec![
-                    vec!['┏', '━', '━', '━', '┓'],
-                    vec!['┃', ' ', '◀', ' ', '┃'],
-                    vec!['┗', '━', '━', '━', '┛'],
-                ],
-                press_sprite: vec![
-                    vec!['╔', '═', '═', '═', '╗'],
-                    vec!['║', ' ', '◀', ' ', '║'],
-                    vec!['╚', '═', '═', '═', '╝'],
-                ],
-                state: State::Normal,
-            },
-            right_button: UiButton {
-                screen_pos: base_pos + ScreenPos::new(6, 0),
-                sprite: vec![
-                    vec!['┌', '─', '─', '─', '┐'],
-                    vec!['│', ' ', '▶', ' ', '│'],
-                    vec!['└', '─', '─', '─', '┘'],
-                ],
-                hover_sprite: vec![
-                    vec!['┏', '━', '━', '━', '┓'],
-                    vec!['┃', ' ', '▶', ' ', '┃'],
-                    vec!['┗', '━', '━', '━', '┛'],
-                ],
-                press_sprite: vec![
-                    vec!['╔', '═', '═', '═', '╗'],
-                    vec!['║', ' ', '▶', ' ', '║'],
-                    vec!['╚', '═', '═', '═', '╝'],
-                ],
-                state: State::Normal,
-            },
-            up_button: UiButton {
-                screen_pos: base_pos + ScreenPos::new(0, -3),
-                sprite: vec![
-                    vec!['┌', '─', '─', '─', '┐'],
-                    vec!['│', ' ', '▲', ' ', '│'],
-                    vec!['└', '─', '─', '─', '┘'],
-                ],
-                hover_sprite: vec![
-                    vec!['┏', '━', '━', '━', '┓'],
-                    vec!['┃', ' ', '▲', ' ', '┃'],
-                    vec!['┗', '━', '━', '━', '┛'],
-                ],
-                press_sprite: vec![
-                    vec!['╔', '═', '═', '═', '╗'],
-                    vec!['║', ' ', '▲', ' ', '║'],
-                    vec!['╚', '═', '═', '═', '╝'],
-                ],
-                state: State::Normal,
-            },
-            down_button: UiButton {
-                screen_pos: base_pos + ScreenPos::new(0, 3),
-                sprite: vec![
-                    vec!['┌', '─', '─', '─', '┐'],
-                    vec!['│', ' ', '▼', ' ', '│'],
-                    vec!['└', '─', '─', '─', '┘'],
-                ],
-                hover_sprite: vec![
-                    vec!['┏', '━', '━', '━', '┓'],
-                    vec!['┃', ' ', '▼', ' ', '┃'],
-                    vec!['┗', '━', '━', '━', '┛'],
-                ],
-                press_sprite: vec![
-                    vec!['╔', '═', '═', '═', '╗'],
-                    vec!['║', ' ', '▼', ' ', '║'],
-                    vec!['╚', '═', '═', '═', '╝'],
-                ],
-                state: State::Normal,
-            },
-            msg_button: UiButton {
-                screen_pos: base_pos,
-                sprite: vec![
-                    vec!['┌', '─', '─', '─', '┐'],
-                    vec!['│', ' ', '?', ' ', '│'],
-                    vec!['└', '─', '─', '─', '┘'],
-                ],
-                hover_sprite: vec![
-                    vec!['┏', '━', '━', '━', '┓'],
-                    vec!['┃', ' ', '?', ' ', '┃'],
-                    vec!['┗', '━', '━', '━', '┛'],
-                ],
-                press_sprite: vec![
-                    vec!['╔', '═', '═', '═', '╗'],
-                    vec!['║', ' ', '?', ' ', '║'],
-                    vec!['╚', '═', '═', '═', '╝'],
-                ],
-                state: State::Normal,
-            },
+            left_button: UiButton::new(base_pos + ScreenPos::new(-6, 0), '◀'),
+            right_button: UiButton::new(base_pos + ScreenPos::new(6, 0), '▶'),
+            up_button: UiButton::new(base_pos + ScreenPos::new(0, -3), '▲'),
+            down_button: UiButton::new(base_pos + ScreenPos::new(0, 3), '▼'),
+            msg_button: UiButton::new(base_pos, '?'),
             msg_button_counter: 0,
         }
     }
@@ -202,6 +137,15 @@ impl UiButtons {
             camera.pos.y -= 5.0;
         }
 
+        // drag with the middle button or turn the wheel to pan around
+        if input
+            .drag()
+            .is_some_and(|drag| drag.button == Button::MiddleMouse)
+        {
+            camera.pos -= input.mouse_delta().into();
+        }
+        camera.pos += Pos::from(input.scroll()).scale(4.0);
+
         if self.msg_button.clicked_this_frame() {
             let msg = format!(
                 "that cheese {}stinks",
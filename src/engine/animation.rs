@@ -1,8 +1,6 @@
 use std::time::Duration;
 
-use crate::game::UPDATE_INTERVAL;
-
-use super::Sprite;
+use super::{into_sprite, key_out, Cell, Clock, Color, Sprite};
 
 pub struct Animation {
     frames: Vec<Sprite>,
@@ -14,9 +12,13 @@ pub struct Animation {
 }
 
 impl Animation {
-    pub fn new(frames: Vec<Sprite>, frame_time: Duration, one_shot: bool) -> Self {
+    pub fn new<T: Into<Cell>>(
+        frames: Vec<Vec<Vec<T>>>,
+        frame_time: Duration,
+        one_shot: bool,
+    ) -> Self {
         Self {
-            frames,
+            frames: frames.into_iter().map(into_sprite).collect(),
             current_frame: 0,
             frame_time,
             frame_time_left: frame_time,
@@ -25,12 +27,28 @@ impl Animation {
         }
     }
 
-    pub fn update(&mut self) {
+    /// Sets the foreground color of every cell in every frame.
+    pub fn tint(mut self, color: Color) -> Self {
+        for cell in self.frames.iter_mut().flatten().flatten() {
+            cell.fg = color;
+        }
+        self
+    }
+
+    /// Makes `key` transparent in every frame.
+    pub fn key_out(mut self, key: char) -> Self {
+        for frame in &mut self.frames {
+            key_out(frame, key);
+        }
+        self
+    }
+
+    pub fn update(&mut self, clock: &Clock) {
         if self.paused {
             return;
         }
 
-        match self.frame_time_left.checked_sub(UPDATE_INTERVAL) {
+        match self.frame_time_left.checked_sub(clock.delta()) {
             Some(time) => self.frame_time_left = time,
             None => {
                 self.frame_time_left = self.frame_time;
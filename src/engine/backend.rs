@@ -0,0 +1,273 @@
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+use crossterm::{cursor, queue, style, terminal};
+
+use super::{Cell, Dimension};
+
+/// Where the renderer sends its output. Everything is expressed in terms of
+/// cursor moves and styled prints, so a backend only has to track a cursor and a pen.
+pub trait Backend {
+    /// Gets the output ready for drawing, e.g. by hiding the cursor.
+    fn setup(&mut self) -> std::io::Result<()>;
+
+    /// Undoes whatever `setup` did.
+    fn restore(&mut self) -> std::io::Result<()>;
+
+    fn clear(&mut self) -> std::io::Result<()>;
+
+    /// Blanks from the cursor to the end of its line.
+    fn clear_line(&mut self) -> std::io::Result<()>;
+
+    fn move_to(&mut self, x: Dimension, y: Dimension) -> std::io::Result<()>;
+
+    /// Uses the style of `cell` for everything printed after this.
+    fn set_style(&mut self, cell: &Cell) -> std::io::Result<()>;
+
+    fn reset_style(&mut self) -> std::io::Result<()>;
+
+    fn print(&mut self, text: &str) -> std::io::Result<()>;
+
+    fn flush(&mut self) -> std::io::Result<()>;
+}
+
+/// Writes crossterm escape sequences to any writer, stdout by default.
+pub struct TerminalBackend<W: Write = std::io::Stdout> {
+    writer: W,
+}
+
+impl<W: Write> TerminalBackend<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl TerminalBackend {
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl<W: Write> Backend for TerminalBackend<W> {
+    fn setup(&mut self) -> std::io::Result<()> {
+        queue!(self.writer, cursor::Hide)
+    }
+
+    /// Leaves the screen contents alone, since `TerminalSession` draws on the
+    /// alternate screen and leaving that puts back whatever was there before.
+    fn restore(&mut self) -> std::io::Result<()> {
+        queue!(
+            self.writer,
+            style::SetAttribute(style::Attribute::Reset),
+            style::ResetColor,
+            cursor::Show,
+        )?;
+        self.writer.flush()
+    }
+
+    fn clear(&mut self) -> std::io::Result<()> {
+        queue!(self.writer, terminal::Clear(terminal::ClearType::All))
+    }
+
+    fn clear_line(&mut self) -> std::io::Result<()> {
+        queue!(
+            self.writer,
+            terminal::Clear(terminal::ClearType::UntilNewLine)
+        )
+    }
+
+    fn move_to(&mut self, x: Dimension, y: Dimension) -> std::io::Result<()> {
+        queue!(self.writer, cursor::MoveTo(x, y))
+    }
+
+    fn set_style(&mut self, cell: &Cell) -> std::io::Result<()> {
+        queue!(
+            self.writer,
+            style::SetAttribute(style::Attribute::Reset),
+            style::SetColors(style::Colors::new(cell.fg, cell.bg))
+        )?;
+
+        if cell.bold {
+            queue!(self.writer, style::SetAttribute(style::Attribute::Bold))?;
+        }
+        if cell.dim {
+            queue!(self.writer, style::SetAttribute(style::Attribute::Dim))?;
+        }
+        if cell.reverse {
+            queue!(self.writer, style::SetAttribute(style::Attribute::Reverse))?;
+        }
+
+        Ok(())
+    }
+
+    fn reset_style(&mut self) -> std::io::Result<()> {
+        queue!(
+            self.writer,
+            style::SetAttribute(style::Attribute::Reset),
+            style::ResetColor
+        )
+    }
+
+    fn print(&mut self, text: &str) -> std::io::Result<()> {
+        queue!(self.writer, style::Print(text))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+struct Screen {
+    cells: Vec<Vec<Cell>>,
+    cursor: (usize, usize),
+    pen: Cell,
+}
+
+/// Keeps the output in an in-memory grid of cells instead of a terminal.
+/// Clones share the same grid, so keep one around to inspect what a renderer drew.
+#[derive(Clone)]
+pub struct MemoryBackend {
+    screen: Rc<RefCell<Screen>>,
+}
+
+impl MemoryBackend {
+    pub fn new(width: Dimension, height: Dimension) -> Self {
+        Self {
+            screen: Rc::new(RefCell::new(Screen {
+                cells: vec![vec![Cell::BLANK; width as usize]; height as usize],
+                cursor: (0, 0),
+                pen: Cell::BLANK,
+            })),
+        }
+    }
+
+    pub fn cells(&self) -> Vec<Vec<Cell>> {
+        self.screen.borrow().cells.clone()
+    }
+
+    pub fn text(&self) -> String {
+        cells_to_text(&self.screen.borrow().cells)
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn setup(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn restore(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn clear(&mut self) -> std::io::Result<()> {
+        let mut screen = self.screen.borrow_mut();
+        for cell in screen.cells.iter_mut().flatten() {
+            *cell = Cell::BLANK;
+        }
+        Ok(())
+    }
+
+    fn clear_line(&mut self) -> std::io::Result<()> {
+        let mut screen = self.screen.borrow_mut();
+        let (x, y) = screen.cursor;
+        if let Some(row) = screen.cells.get_mut(y) {
+            for cell in row.iter_mut().skip(x) {
+                *cell = Cell::BLANK;
+            }
+        }
+        Ok(())
+    }
+
+    fn move_to(&mut self, x: Dimension, y: Dimension) -> std::io::Result<()> {
+        self.screen.borrow_mut().cursor = (x as usize, y as usize);
+        Ok(())
+    }
+
+    fn set_style(&mut self, cell: &Cell) -> std::io::Result<()> {
+        self.screen.borrow_mut().pen = *cell;
+        Ok(())
+    }
+
+    fn reset_style(&mut self) -> std::io::Result<()> {
+        self.screen.borrow_mut().pen = Cell::BLANK;
+        Ok(())
+    }
+
+    fn print(&mut self, text: &str) -> std::io::Result<()> {
+        let mut screen = self.screen.borrow_mut();
+        let (mut x, y) = screen.cursor;
+        let pen = screen.pen;
+        for glyph in text.chars() {
+            if let Some(cell) = screen.cells.get_mut(y).and_then(|row| row.get_mut(x)) {
+                *cell = Cell { glyph, ..pen };
+            }
+            x += 1;
+        }
+        screen.cursor = (x, y);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Renders just the glyphs of a grid, one line per row, with trailing blanks trimmed
+/// so golden files don't depend on invisible whitespace.
+pub fn cells_to_text(cells: &[Vec<Cell>]) -> String {
+    let mut text = String::new();
+    for row in cells {
+        let line = String::from_iter(row.iter().map(|cell| cell.glyph));
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Compares `actual` against the golden file at `path`, panicking on the first
+/// differing line, or if there's no golden file. Set `SPLAT_UPDATE_SNAPSHOTS` to
+/// write the golden files instead.
+#[cfg(test)]
+pub(crate) fn assert_snapshot(actual: &str, path: &std::path::Path) {
+    if std::env::var_os("SPLAT_UPDATE_SNAPSHOTS").is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).expect("failed to create snapshot dir");
+        }
+        std::fs::write(path, actual).expect("failed to write snapshot");
+        return;
+    }
+    if !path.exists() {
+        panic!(
+            "missing snapshot {}, rerun with SPLAT_UPDATE_SNAPSHOTS=1",
+            path.display()
+        );
+    }
+
+    let expected = std::fs::read_to_string(path).expect("failed to read snapshot");
+    if expected == actual {
+        return;
+    }
+
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => line += 1,
+            (None, None) => panic!("snapshot {} differs in line endings", path.display()),
+            (e, a) => panic!(
+                "snapshot {} differs at line {line}\nexpected: {:?}\n  actual: {:?}",
+                path.display(),
+                e.unwrap_or("<end of file>"),
+                a.unwrap_or("<end of file>"),
+            ),
+        }
+    }
+}
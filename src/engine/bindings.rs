@@ -0,0 +1,529 @@
+use std::{fmt::Display, path::Path};
+
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton};
+
+use super::Button;
+
+impl Button {
+    /// Every button that can be bound, in the order bindings are listed.
+    /// The mouse buttons always follow the physical ones.
+    pub const BINDABLE: [Self; 17] = [
+        Self::Quit,
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::Jump,
+        Self::Grapple,
+        Self::Reset,
+        Self::Space,
+        Self::Map,
+        Self::Record,
+        Self::Screenshot,
+        Self::Console,
+        Self::ConsoleLevel,
+        Self::ConsoleTarget,
+        Self::PageUp,
+        Self::PageDown,
+    ];
+
+    /// The name used for the button in bindings files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Jump => "jump",
+            Self::Grapple => "grapple",
+            Self::Reset => "reset",
+            Self::Space => "space",
+            Self::Map => "map",
+            Self::Record => "record",
+            Self::Screenshot => "screenshot",
+            Self::Console => "console",
+            Self::ConsoleLevel => "console_level",
+            Self::ConsoleTarget => "console_target",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+            Self::LeftMouse => "left_mouse",
+            Self::RightMouse => "right_mouse",
+            Self::MiddleMouse => "middle_mouse",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::BINDABLE
+            .into_iter()
+            .find(|button| button.name() == name)
+    }
+}
+
+/// Which way the scroll wheel turned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wheel {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// Each notch presses whatever it's bound to for a single frame.
+    Wheel(Wheel),
+}
+
+/// A key or mouse button, along with modifiers that have to be held for it to count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Binding {
+    pub trigger: Trigger,
+    pub modifiers: KeyModifiers,
+}
+
+impl Binding {
+    pub const fn key(code: KeyCode) -> Self {
+        Self {
+            trigger: Trigger::Key(code),
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    pub const fn char(c: char) -> Self {
+        Self::key(KeyCode::Char(c))
+    }
+
+    pub const fn mouse(button: MouseButton) -> Self {
+        Self {
+            trigger: Trigger::Mouse(button),
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    pub const fn wheel(wheel: Wheel) -> Self {
+        Self {
+            trigger: Trigger::Wheel(wheel),
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    pub const fn with(mut self, modifiers: KeyModifiers) -> Self {
+        self.modifiers = self.modifiers.union(modifiers);
+        self
+    }
+
+    /// Parses names like `w`, `space`, `f9`, `mouse_right`, `wheel_up` or `ctrl+c`.
+    /// The keys that mean something in bindings files go by `hash`, `comma` and `plus`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = spec.split('+').map(str::trim).collect();
+        let name = parts.pop()?;
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+        }
+
+        let trigger = match name.to_lowercase().as_str() {
+            "mouse_left" => Trigger::Mouse(MouseButton::Left),
+            "mouse_right" => Trigger::Mouse(MouseButton::Right),
+            "mouse_middle" => Trigger::Mouse(MouseButton::Middle),
+            "wheel_up" => Trigger::Wheel(Wheel::Up),
+            "wheel_down" => Trigger::Wheel(Wheel::Down),
+            "wheel_left" => Trigger::Wheel(Wheel::Left),
+            "wheel_right" => Trigger::Wheel(Wheel::Right),
+            "esc" => Trigger::Key(KeyCode::Esc),
+            "enter" => Trigger::Key(KeyCode::Enter),
+            "tab" => Trigger::Key(KeyCode::Tab),
+            "backspace" => Trigger::Key(KeyCode::Backspace),
+            "space" => Trigger::Key(KeyCode::Char(' ')),
+            "hash" => Trigger::Key(KeyCode::Char('#')),
+            "comma" => Trigger::Key(KeyCode::Char(',')),
+            "plus" => Trigger::Key(KeyCode::Char('+')),
+            "up" => Trigger::Key(KeyCode::Up),
+            "down" => Trigger::Key(KeyCode::Down),
+            "left" => Trigger::Key(KeyCode::Left),
+            "right" => Trigger::Key(KeyCode::Right),
+            "page_up" => Trigger::Key(KeyCode::PageUp),
+            "page_down" => Trigger::Key(KeyCode::PageDown),
+            "home" => Trigger::Key(KeyCode::Home),
+            "end" => Trigger::Key(KeyCode::End),
+            lower => match (lower.strip_prefix('f'), name.chars().count()) {
+                (Some(n), _) if !n.is_empty() => Trigger::Key(KeyCode::F(n.parse().ok()?)),
+                (_, 1) => Trigger::Key(KeyCode::Char(Self::fold(name.chars().next()?))),
+                _ => return None,
+            },
+        };
+
+        Some(Self { trigger, modifiers })
+    }
+
+    /// Whether an event from `trigger` with `modifiers` held sets this off.
+    /// Letters match regardless of case, since shift is just another modifier.
+    fn matches(&self, trigger: Trigger, modifiers: KeyModifiers) -> bool {
+        let trigger = match trigger {
+            Trigger::Key(KeyCode::Char(c)) => Trigger::Key(KeyCode::Char(Self::fold(c))),
+            trigger => trigger,
+        };
+        self.trigger == trigger && modifiers.contains(self.modifiers)
+    }
+
+    fn fold(c: char) -> char {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl"),
+            (KeyModifiers::ALT, "alt"),
+            (KeyModifiers::SHIFT, "shift"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match self.trigger {
+            Trigger::Mouse(MouseButton::Left) => write!(f, "mouse_left"),
+            Trigger::Mouse(MouseButton::Right) => write!(f, "mouse_right"),
+            Trigger::Mouse(MouseButton::Middle) => write!(f, "mouse_middle"),
+            Trigger::Wheel(Wheel::Up) => write!(f, "wheel_up"),
+            Trigger::Wheel(Wheel::Down) => write!(f, "wheel_down"),
+            Trigger::Wheel(Wheel::Left) => write!(f, "wheel_left"),
+            Trigger::Wheel(Wheel::Right) => write!(f, "wheel_right"),
+            Trigger::Key(KeyCode::Char(' ')) => write!(f, "space"),
+            Trigger::Key(KeyCode::Char('#')) => write!(f, "hash"),
+            Trigger::Key(KeyCode::Char(',')) => write!(f, "comma"),
+            Trigger::Key(KeyCode::Char('+')) => write!(f, "plus"),
+            Trigger::Key(KeyCode::Char(c)) => write!(f, "{c}"),
+            Trigger::Key(KeyCode::F(n)) => write!(f, "f{n}"),
+            Trigger::Key(KeyCode::Esc) => write!(f, "esc"),
+            Trigger::Key(KeyCode::Enter) => write!(f, "enter"),
+            Trigger::Key(KeyCode::Tab) => write!(f, "tab"),
+            Trigger::Key(KeyCode::Backspace) => write!(f, "backspace"),
+            Trigger::Key(KeyCode::Up) => write!(f, "up"),
+            Trigger::Key(KeyCode::Down) => write!(f, "down"),
+            Trigger::Key(KeyCode::Left) => write!(f, "left"),
+            Trigger::Key(KeyCode::Right) => write!(f, "right"),
+            Trigger::Key(KeyCode::PageUp) => write!(f, "page_up"),
+            Trigger::Key(KeyCode::PageDown) => write!(f, "page_down"),
+            Trigger::Key(KeyCode::Home) => write!(f, "home"),
+            Trigger::Key(KeyCode::End) => write!(f, "end"),
+            Trigger::Key(code) => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Which keys and mouse buttons drive which `Button`s.
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    bindings: Vec<(Button, Binding)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use KeyCode::*;
+
+        let bindings = [
+            (Button::Quit, Binding::key(Esc)),
+            (Button::Quit, Binding::char('c').with(KeyModifiers::CONTROL)),
+            (Button::Up, Binding::char('w')),
+            (Button::Up, Binding::key(Up)),
+            (Button::Down, Binding::char('s')),
+            (Button::Down, Binding::key(Down)),
+            (Button::Left, Binding::char('a')),
+            (Button::Left, Binding::key(Left)),
+            (Button::Right, Binding::char('d')),
+            (Button::Right, Binding::key(Right)),
+            (Button::Jump, Binding::mouse(MouseButton::Right)),
+            (Button::Grapple, Binding::mouse(MouseButton::Left)),
+            (Button::Reset, Binding::char('r')),
+            (Button::Space, Binding::char(' ')),
+            (Button::Map, Binding::char('m')),
+            (Button::Record, Binding::key(F(9))),
+            (Button::Screenshot, Binding::key(F(8))),
+            (Button::Console, Binding::char('`')),
+            (Button::ConsoleLevel, Binding::key(F(2))),
+            (Button::ConsoleTarget, Binding::key(F(3))),
+            (Button::PageUp, Binding::key(PageUp)),
+            (Button::PageDown, Binding::key(PageDown)),
+        ];
+
+        Self {
+            bindings: bindings.to_vec(),
+        }
+    }
+}
+
+impl Bindings {
+    pub const FILE_VAR: &'static str = "SPLAT_BINDINGS";
+
+    /// Loaded from the file `SPLAT_BINDINGS` points to, or the defaults if it isn't set.
+    pub fn from_env() -> std::io::Result<Self> {
+        match std::env::var_os(Self::FILE_VAR) {
+            Some(path) => Self::load(Path::new(&path)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// No bindings at all.
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// The defaults, with every button mentioned in `path` rebound as it says.
+    /// Each line looks like `jump = mouse_left, space`, and `#` starts a comment.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let mut bindings = Self::default();
+        bindings
+            .apply(&std::fs::read_to_string(path)?)
+            .map_err(|msg| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{}: {msg}", path.display()),
+                )
+            })?;
+        Ok(bindings)
+    }
+
+    /// Rebinds the buttons named in `config`, in the format `load` reads.
+    pub fn apply(&mut self, config: &str) -> Result<(), String> {
+        for (i, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let err = |msg: String| format!("line {}: {msg}", i + 1);
+            let (name, specs) = line
+                .split_once('=')
+                .ok_or_else(|| err(format!("expected `button = keys`, got {line:?}")))?;
+            let button = Button::from_name(name.trim())
+                .ok_or_else(|| err(format!("unknown button {:?}", name.trim())))?;
+
+            let mut bindings = Vec::new();
+            for spec in specs
+                .split(',')
+                .map(str::trim)
+                .filter(|spec| !spec.is_empty())
+            {
+                let binding =
+                    Binding::parse(spec).ok_or_else(|| err(format!("unknown key {spec:?}")))?;
+                bindings.push(binding);
+            }
+            self.set(button, bindings);
+        }
+        Ok(())
+    }
+
+    /// Replaces all of `button`'s bindings. An empty list unbinds it.
+    pub fn set(&mut self, button: Button, bindings: impl IntoIterator<Item = Binding>) {
+        self.bindings.retain(|(bound, _)| *bound != button);
+        self.bindings
+            .extend(bindings.into_iter().map(|binding| (button, binding)));
+    }
+
+    pub fn bind(&mut self, button: Button, binding: Binding) {
+        if !self.bindings.contains(&(button, binding)) {
+            self.bindings.push((button, binding));
+        }
+    }
+
+    pub fn get(&self, button: Button) -> impl Iterator<Item = Binding> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(bound, _)| *bound == button)
+            .map(|(_, binding)| *binding)
+    }
+
+    /// Every bindable button with what it's bound to, in `Button::BINDABLE` order.
+    pub fn list(&self) -> Vec<(Button, Vec<Binding>)> {
+        Button::BINDABLE
+            .into_iter()
+            .map(|button| (button, self.get(button).collect()))
+            .collect()
+    }
+
+    /// Problems worth telling the player about: buttons that can't be pressed,
+    /// and keys that set off more than one button at once.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (button, bindings) in self.list() {
+            if bindings.is_empty() {
+                problems.push(format!("{} is not bound to anything", button.name()));
+            }
+        }
+
+        for (i, (button, binding)) in self.bindings.iter().enumerate() {
+            for (other, other_binding) in &self.bindings[i + 1..] {
+                if binding == other_binding && button != other {
+                    problems.push(format!(
+                        "{binding} is bound to both {} and {}",
+                        button.name(),
+                        other.name()
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+
+    /// The buttons an event from `trigger` sets off. When bindings with and without
+    /// modifiers both match, only the most specific ones count, so ctrl+c doesn't
+    /// also press whatever plain c is bound to.
+    pub fn buttons(&self, trigger: Trigger, modifiers: KeyModifiers) -> Vec<Button> {
+        let matching = self
+            .bindings
+            .iter()
+            .filter(|(_, binding)| binding.matches(trigger, modifiers));
+        let specificity = |binding: &Binding| binding.modifiers.bits().count_ones();
+        let best = matching
+            .clone()
+            .map(|(_, binding)| specificity(binding))
+            .max();
+
+        matching
+            .filter(|(_, binding)| Some(specificity(binding)) == best)
+            .map(|(button, _)| *button)
+            .collect()
+    }
+}
+
+impl Display for Bindings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (button, bindings) in self.list() {
+            let bindings: Vec<String> = bindings.iter().map(Binding::to_string).collect();
+            let line = format!("{} = {}", button.name(), bindings.join(", "));
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_binding() {
+        assert_eq!(Binding::parse("w"), Some(Binding::char('w')));
+        assert_eq!(Binding::parse("W"), Some(Binding::char('w')));
+        assert_eq!(Binding::parse("space"), Some(Binding::char(' ')));
+        assert_eq!(Binding::parse("f9"), Some(Binding::key(KeyCode::F(9))));
+        assert_eq!(Binding::parse("f"), Some(Binding::char('f')));
+        assert_eq!(
+            Binding::parse("mouse_right"),
+            Some(Binding::mouse(MouseButton::Right))
+        );
+        assert_eq!(Binding::parse("wheel_up"), Some(Binding::wheel(Wheel::Up)));
+        assert_eq!(
+            Binding::parse("ctrl + shift+c"),
+            Some(Binding::char('c').with(KeyModifiers::CONTROL | KeyModifiers::SHIFT))
+        );
+        assert_eq!(Binding::parse("hash"), Some(Binding::char('#')));
+        assert_eq!(Binding::parse("comma"), Some(Binding::char(',')));
+        assert_eq!(Binding::parse("plus"), Some(Binding::char('+')));
+
+        assert_eq!(Binding::parse(""), None);
+        assert_eq!(Binding::parse("super+c"), None);
+        assert_eq!(Binding::parse("jump"), None);
+        assert_eq!(Binding::parse("fx"), None);
+    }
+
+    #[test]
+    fn applies_config() {
+        let mut bindings = Bindings::default();
+        let config = "\
+# a comment
+jump = mouse_left, space # and one after the bindings
+  grapple=hash,comma
+
+reset =
+";
+        bindings.apply(config).unwrap();
+
+        let jump: Vec<_> = bindings.get(Button::Jump).collect();
+        assert_eq!(
+            jump,
+            [Binding::mouse(MouseButton::Left), Binding::char(' ')]
+        );
+        let grapple: Vec<_> = bindings.get(Button::Grapple).collect();
+        assert_eq!(grapple, [Binding::char('#'), Binding::char(',')]);
+        assert_eq!(bindings.get(Button::Reset).count(), 0);
+        // buttons the config doesn't mention keep their defaults
+        assert_eq!(
+            bindings.get(Button::Map).collect::<Vec<_>>(),
+            [Binding::char('m')]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_config() {
+        let mut bindings = Bindings::default();
+        assert_eq!(
+            bindings.apply("jump = mouse_left\njump = nope"),
+            Err("line 2: unknown key \"nope\"".to_string())
+        );
+        assert_eq!(
+            bindings.apply("hop = space"),
+            Err("line 1: unknown button \"hop\"".to_string())
+        );
+        assert!(bindings.apply("jump space").is_err());
+    }
+
+    #[test]
+    fn finds_conflicts() {
+        let mut bindings = Bindings::default();
+        assert!(bindings.validate().is_empty());
+
+        bindings.set(Button::Map, [Binding::char('r')]);
+        bindings.set(Button::Screenshot, []);
+        assert_eq!(
+            bindings.validate(),
+            [
+                "screenshot is not bound to anything",
+                "r is bound to both reset and map",
+            ]
+        );
+
+        // the same key with a modifier is a different binding
+        bindings.set(
+            Button::Map,
+            [Binding::char('r').with(KeyModifiers::CONTROL)],
+        );
+        bindings.set(Button::Screenshot, [Binding::key(KeyCode::F(8))]);
+        assert!(bindings.validate().is_empty());
+    }
+
+    #[test]
+    fn listing_loads_back() {
+        let mut bindings = Bindings::default();
+        bindings.set(
+            Button::Map,
+            [
+                Binding::char('#'),
+                Binding::char(','),
+                Binding::char('+').with(KeyModifiers::ALT),
+                Binding::char('='),
+            ],
+        );
+        bindings.set(Button::Reset, []);
+
+        let mut loaded = Bindings::empty();
+        loaded.apply(&bindings.to_string()).unwrap();
+        assert_eq!(loaded.list(), bindings.list());
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use super::{Camera, Cell, Color, Coord, Drawable, Pos, Ray, Renderer, ScreenCoord, ScreenPos};
+
+/// Draws in world space at braille resolution, 2x4 sub-dots per screen cell.
+/// Since a cell is one unit wide and two units tall, a sub-dot is half a unit square.
+pub struct BrailleCanvas {
+    cells: HashMap<(ScreenCoord, ScreenCoord), (u8, Color)>,
+}
+
+impl BrailleCanvas {
+    const BLANK: u32 = 0x2800;
+
+    /// Sub-dots per world unit, the same along both axes.
+    const DOTS_PER_UNIT: Coord = 2.0;
+
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Sets the sub-dot under `pos`. The cell takes on the color of its latest dot.
+    pub fn plot(&mut self, pos: Pos, color: Color) {
+        let dot_x = (pos.x * Self::DOTS_PER_UNIT).floor() as ScreenCoord;
+        let dot_y = (pos.y * Self::DOTS_PER_UNIT).floor() as ScreenCoord;
+
+        let cell = (dot_x.div_euclid(2), dot_y.div_euclid(4));
+        let bit = Self::bit(dot_x.rem_euclid(2), dot_y.rem_euclid(4));
+
+        let entry = self.cells.entry(cell).or_insert((0, color));
+        entry.0 |= bit;
+        entry.1 = color;
+    }
+
+    /// Plots every sub-dot along `ray`.
+    pub fn plot_ray(&mut self, ray: Ray, color: Color) {
+        let step = 0.5 / Self::DOTS_PER_UNIT;
+        let steps = (ray.length() / step).ceil() as usize;
+        let delta = ray.end - ray.start;
+
+        self.plot(ray.start, color);
+        for i in 1..=steps {
+            self.plot(ray.start + delta.scale(i as Coord / steps as Coord), color);
+        }
+    }
+
+    /// Braille bit for the sub-dot at column `x` and row `y` of a cell, per the
+    /// Unicode layout where the bottom row was tacked on after the first three.
+    pub fn bit(x: ScreenCoord, y: ScreenCoord) -> u8 {
+        match (x, y) {
+            (0, 3) => 0x40,
+            (1, 3) => 0x80,
+            (x, y) => 1 << (y + x * 3),
+        }
+    }
+
+    /// The braille character with the sub-dots in `bits` set, see `bit`.
+    pub fn glyph(bits: u8) -> char {
+        char::from_u32(Self::BLANK | bits as u32).unwrap_or(' ')
+    }
+}
+
+impl Drawable for BrailleCanvas {
+    fn draw(&self, camera: &Camera, renderer: &mut Renderer) {
+        for (&(x, y), &(bits, color)) in &self.cells {
+            camera.paint_dot(
+                Cell::new(Self::glyph(bits)).fg(color),
+                ScreenPos::new(x, y).into(),
+                renderer,
+            );
+        }
+    }
+}
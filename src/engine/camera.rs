@@ -1,15 +1,78 @@
-use super::{Dimension, Pos, Renderer, ScreenCoord, ScreenPos, Sprite};
+use super::{CameraEffects, Cell, Coord, Dimension, Pos, Renderer, ScreenCoord, ScreenPos};
 
 pub struct Camera {
     pub pos: Pos,
     pub frame_pos: ScreenPos,
     pub width: Dimension,
     pub height: Dimension,
+    pub effects: CameraEffects,
 }
 
 impl Camera {
-    pub fn paint_sprite(&self, sprite: &Sprite, pos: Pos, renderer: &mut Renderer) {
-        let cam_screen_pos = ScreenPos::from(self.pos);
+    /// Screen coords of the top left of what's drawn, which `effects` can nudge
+    /// away from `pos`.
+    fn view_pos(&self) -> ScreenPos {
+        ScreenPos::from(self.pos) + self.effects.offset()
+    }
+
+    fn center(&self) -> ScreenPos {
+        ScreenPos::new((self.width / 2).into(), (self.height / 2).into())
+    }
+
+    /// Where a cell `rel` from the top left of the view lands once zoomed.
+    fn zoom_to_view(&self, rel: ScreenPos, zoom: Coord) -> ScreenPos {
+        let center = self.center();
+        let scale = |rel: ScreenCoord, center: ScreenCoord| {
+            center + ((rel - center) as Coord * zoom).round() as ScreenCoord
+        };
+        ScreenPos::new(scale(rel.x, center.x), scale(rel.y, center.y))
+    }
+
+    /// The inverse of `zoom_to_view`.
+    fn zoom_from_view(&self, rel: ScreenPos, zoom: Coord) -> ScreenPos {
+        self.zoom_to_view(rel, 1.0 / zoom)
+    }
+
+    /// Moves the view so `target` is in the middle of it.
+    pub fn center_on(&mut self, target: Pos) {
+        let quantized = ScreenPos::from(target) - self.center();
+        self.pos = quantized.into();
+    }
+
+    /// Keeps the view inside the `extent` cells starting at `origin`, centering it
+    /// along any axis where the area is smaller than the view.
+    pub fn clamp_to(&mut self, origin: ScreenPos, extent: ScreenPos) {
+        let pos = ScreenPos::from(self.pos);
+        let view = ScreenPos::new(self.width.into(), self.height.into());
+
+        let clamp_axis = |pos: ScreenCoord, origin: ScreenCoord, extent: ScreenCoord, view| {
+            if extent <= view {
+                origin - (view - extent) / 2
+            } else {
+                pos.clamp(origin, origin + extent - view)
+            }
+        };
+
+        self.pos = ScreenPos::new(
+            clamp_axis(pos.x, origin.x, extent.x, view.x),
+            clamp_axis(pos.y, origin.y, extent.y, view.y),
+        )
+        .into();
+    }
+
+    pub fn paint_sprite<T: Copy + Into<Cell>>(
+        &self,
+        sprite: &[Vec<T>],
+        pos: Pos,
+        renderer: &mut Renderer,
+    ) {
+        let zoom = self.effects.zoom();
+        if zoom != 1.0 {
+            self.paint_sprite_zoomed(sprite, pos, zoom, renderer);
+            return;
+        }
+
+        let cam_screen_pos = self.view_pos();
         let sprite_screen_pos = ScreenPos::from(pos);
 
         let start_y = cam_screen_pos.y.max(sprite_screen_pos.y);
@@ -39,8 +102,39 @@ impl Camera {
         }
     }
 
-    pub fn paint_dot(&self, dot: char, pos: Pos, renderer: &mut Renderer) {
-        let dot_rel_pos = ScreenPos::from(pos - self.pos);
+    /// Samples the sprite under every cell of the view, since zooming out maps
+    /// several sprite cells onto one.
+    fn paint_sprite_zoomed<T: Copy + Into<Cell>>(
+        &self,
+        sprite: &[Vec<T>],
+        pos: Pos,
+        zoom: Coord,
+        renderer: &mut Renderer,
+    ) {
+        let origin = self.view_pos() - ScreenPos::from(pos);
+
+        for y in 0..self.height as ScreenCoord {
+            for x in 0..self.width as ScreenCoord {
+                let sprite_pos = origin + self.zoom_from_view(ScreenPos::new(x, y), zoom);
+                if sprite_pos.x < 0 || sprite_pos.y < 0 {
+                    continue;
+                }
+
+                let (sprite_x, sprite_y) = sprite_pos.indices();
+                if let Some(dot) = sprite.get(sprite_y).and_then(|row| row.get(sprite_x)) {
+                    renderer.paint(
+                        (self.frame_pos.x + x) as Dimension,
+                        (self.frame_pos.y + y) as Dimension,
+                        *dot,
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn paint_dot(&self, dot: impl Into<Cell>, pos: Pos, renderer: &mut Renderer) {
+        let dot_rel_pos = ScreenPos::from(pos - self.pos) - self.effects.offset();
+        let dot_rel_pos = self.zoom_to_view(dot_rel_pos, self.effects.zoom());
 
         if dot_rel_pos.x >= 0
             && dot_rel_pos.x < self.width as ScreenCoord
@@ -55,3 +149,62 @@ impl Camera {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::engine::{assert_snapshot, into_sprite, Sprite};
+
+    fn snapshot_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/engine/snapshots")
+            .join(format!("{name}.txt"))
+    }
+
+    /// A 10x4 view drawn one cell in from the corner of a 12x6 terminal.
+    fn camera(pos: Pos) -> Camera {
+        Camera {
+            pos,
+            frame_pos: ScreenPos::new(1, 1),
+            width: 10,
+            height: 4,
+            effects: CameraEffects::new(),
+        }
+    }
+
+    fn sprite() -> Sprite {
+        into_sprite(
+            ["abcd", "efgh", "ijkl"]
+                .iter()
+                .map(|row| row.chars().collect())
+                .collect(),
+        )
+    }
+
+    fn render(camera: &Camera, sprite_positions: &[Pos]) -> String {
+        let (mut renderer, backend) = Renderer::headless(12, 6);
+        for pos in sprite_positions {
+            camera.paint_sprite(&sprite(), *pos, &mut renderer);
+        }
+        renderer.render().unwrap();
+        backend.text()
+    }
+
+    #[test]
+    fn paints_sprite() {
+        let text = render(&camera(Pos::ZERO), &[Pos::new(3.0, 2.0)]);
+        assert_snapshot(&text, &snapshot_path("paints_sprite"));
+    }
+
+    #[test]
+    fn clips_sprite_to_camera() {
+        // one sprite hanging off the top left of the view and one off the bottom right
+        let text = render(
+            &camera(Pos::new(2.0, 2.0)),
+            &[Pos::new(0.0, 0.0), Pos::new(10.0, 8.0)],
+        );
+        assert_snapshot(&text, &snapshot_path("clips_sprite_to_camera"));
+    }
+}
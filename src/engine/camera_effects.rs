@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use super::{Clock, Coord, Pos, ScreenCoord, ScreenPos};
+
+/// Purely visual disturbances of a `Camera`: shake, kicks, zoom and hit-stop.
+/// They only offset what gets drawn, the camera position itself (and so mouse
+/// picking and collision alignment) is never touched.
+#[derive(Debug, Clone)]
+pub struct CameraEffects {
+    trauma: f32,
+    hit_stop: Duration,
+    kick: Pos,
+    zoom_out: f32,
+    offset: ScreenPos,
+}
+
+impl CameraEffects {
+    /// Trauma lost per second.
+    const TRAUMA_DECAY: f32 = 1.5;
+
+    /// Shake at full trauma, in cells.
+    const MAX_SHAKE: Pos = Pos::new(3.0, 1.5);
+
+    /// How quickly kicks and zoom spring back, per second.
+    const RECOVERY_RATE: f32 = 12.0;
+
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            trauma: 0.0,
+            hit_stop: Duration::ZERO,
+            kick: Pos::ZERO,
+            zoom_out: 0.0,
+            offset: ScreenPos::ZERO,
+        }
+    }
+
+    /// Adds trauma, clamped to 1. Shake grows with the square of trauma,
+    /// so small hits barely register and big ones stack up quickly.
+    pub fn shake(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+
+    /// Freezes gameplay for `duration` of simulation time, see `frozen`.
+    pub fn hit_stop(&mut self, duration: Duration) {
+        self.hit_stop = self.hit_stop.max(duration);
+    }
+
+    /// Knocks the view by `offset` in world units, which then springs back.
+    pub fn kick(&mut self, offset: Pos) {
+        self.kick += offset;
+    }
+
+    /// Pulls back so the view shows `1 + amount` times as much, then eases back in.
+    pub fn zoom_out(&mut self, amount: f32) {
+        self.zoom_out = self.zoom_out.max(amount);
+    }
+
+    /// Whether a hit-stop is in progress and gameplay should skip this step.
+    pub fn frozen(&self) -> bool {
+        !self.hit_stop.is_zero()
+    }
+
+    pub fn update(&mut self, clock: &Clock) {
+        let dt = clock.delta_secs();
+
+        self.hit_stop = self.hit_stop.saturating_sub(clock.delta());
+        self.trauma = (self.trauma - Self::TRAUMA_DECAY * dt).max(0.0);
+
+        let recovery = (-Self::RECOVERY_RATE * dt).exp();
+        self.kick = self.kick.scale(recovery);
+        self.zoom_out *= recovery;
+        if self.zoom_out < 0.01 {
+            self.zoom_out = 0.0;
+        }
+
+        // a few incommensurate sines read as noise but stay deterministic
+        let t = clock.now().as_secs_f32();
+        let noise = Pos::new(
+            (t * 53.0).sin() * 0.6 + (t * 97.0).sin() * 0.4,
+            (t * 61.0).sin() * 0.6 + (t * 89.0).cos() * 0.4,
+        );
+        let shake = noise * Self::MAX_SHAKE.scale(self.trauma.powi(2));
+
+        self.offset = ScreenPos::new(
+            (shake.x + self.kick.x).round() as ScreenCoord,
+            (shake.y + self.kick.y / 2.0).round() as ScreenCoord,
+        );
+    }
+
+    /// Cells to shift everything drawn by.
+    pub fn offset(&self) -> ScreenPos {
+        self.offset
+    }
+
+    /// Scale applied about the center of the view, 1 when not zoomed.
+    pub fn zoom(&self) -> Coord {
+        1.0 / (1.0 + self.zoom_out)
+    }
+}
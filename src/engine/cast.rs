@@ -0,0 +1,108 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use super::{Backend, Cell, Dimension, TerminalBackend};
+
+/// Records everything sent to the terminal as an asciicast v2 file,
+/// one output event per flush, so runs can be replayed in any asciinema player.
+pub struct CastRecorder {
+    file: BufWriter<File>,
+    terminal: TerminalBackend<Vec<u8>>,
+    start_time: Instant,
+}
+
+impl CastRecorder {
+    pub fn create(path: &Path, width: Dimension, height: Dimension) -> std::io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+        writeln!(
+            file,
+            r#"{{"version": 2, "width": {width}, "height": {height}, "timestamp": {timestamp}}}"#
+        )?;
+
+        Ok(Self {
+            file,
+            terminal: TerminalBackend::new(Vec::new()),
+            start_time: Instant::now(),
+        })
+    }
+
+    pub fn resize(&mut self, width: Dimension, height: Dimension) -> std::io::Result<()> {
+        self.event("r", &format!("{width}x{height}"))
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> std::io::Result<()> {
+        let time = self.start_time.elapsed().as_secs_f64();
+        writeln!(
+            self.file,
+            "[{time:.6}, \"{code}\", \"{}\"]",
+            escape_json(data)
+        )?;
+        self.file.flush()
+    }
+}
+
+impl Backend for CastRecorder {
+    fn setup(&mut self) -> std::io::Result<()> {
+        self.terminal.setup()
+    }
+
+    fn restore(&mut self) -> std::io::Result<()> {
+        self.terminal.restore()?;
+        self.flush()
+    }
+
+    fn clear(&mut self) -> std::io::Result<()> {
+        self.terminal.clear()
+    }
+
+    fn clear_line(&mut self) -> std::io::Result<()> {
+        self.terminal.clear_line()
+    }
+
+    fn move_to(&mut self, x: Dimension, y: Dimension) -> std::io::Result<()> {
+        self.terminal.move_to(x, y)
+    }
+
+    fn set_style(&mut self, cell: &Cell) -> std::io::Result<()> {
+        self.terminal.set_style(cell)
+    }
+
+    fn reset_style(&mut self) -> std::io::Result<()> {
+        self.terminal.reset_style()
+    }
+
+    fn print(&mut self, text: &str) -> std::io::Result<()> {
+        self.terminal.print(text)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let output = std::mem::take(self.terminal.get_mut());
+        if output.is_empty() {
+            return Ok(());
+        }
+        self.event("o", &String::from_utf8_lossy(&output))
+    }
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
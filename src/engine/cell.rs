@@ -0,0 +1,80 @@
+pub use crossterm::style::Color;
+
+/// A single screen dot: the glyph plus how the terminal should style it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub fg: Color,
+    pub bg: Color,
+    pub bold: bool,
+    pub dim: bool,
+    pub reverse: bool,
+}
+
+impl Cell {
+    pub const BLANK: Self = Self::new(' ');
+
+    /// Painting this leaves whatever is already underneath untouched.
+    pub const TRANSPARENT: Self = Self::new('\0');
+
+    pub const fn new(glyph: char) -> Self {
+        Self {
+            glyph,
+            fg: Color::Reset,
+            bg: Color::Reset,
+            bold: false,
+            dim: false,
+            reverse: false,
+        }
+    }
+
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = color;
+        self
+    }
+
+    pub const fn bg(mut self, color: Color) -> Self {
+        self.bg = color;
+        self
+    }
+
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub const fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    pub const fn reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.glyph == Self::TRANSPARENT.glyph
+    }
+
+    /// Whether two cells can be printed back to back without touching the terminal style.
+    pub fn same_style(&self, other: &Self) -> bool {
+        self.fg == other.fg
+            && self.bg == other.bg
+            && self.bold == other.bold
+            && self.dim == other.dim
+            && self.reverse == other.reverse
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::BLANK
+    }
+}
+
+impl From<char> for Cell {
+    fn from(value: char) -> Self {
+        Self::new(value)
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+
+use log::LevelFilter;
+
+use super::{
+    Align, BorderStyle, Button, Camera, Cell, Color, Dimension, Drawable, Input, Layer, LogRecord,
+    Logger, Panel, Rect, Renderer, Span,
+};
+
+/// Overlay showing the most recent log records, with scrollback and filtering.
+/// Toggle it with `, scroll with PageUp/PageDown, cycle the level with F2 and
+/// the target with F3.
+pub struct LogConsole {
+    logger: &'static Logger,
+    /// The records shown, filtered from the logger's buffer.
+    records: VecDeque<LogRecord>,
+    /// Logger generation `records` are filtered up to, `None` once they're stale.
+    generation: Option<u64>,
+    visible: bool,
+    /// Lines scrolled back from the newest record.
+    scroll: usize,
+    level: LevelFilter,
+    target: Option<String>,
+}
+
+impl LogConsole {
+    /// Fraction of the frame height the console covers.
+    const HEIGHT_RATIO: f32 = 0.4;
+
+    pub fn new(logger: &'static Logger) -> Self {
+        Self {
+            logger,
+            records: VecDeque::new(),
+            generation: None,
+            visible: false,
+            scroll: 0,
+            level: LevelFilter::Trace,
+            target: None,
+        }
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Where the console goes in a frame `width` by `height` cells.
+    fn rect(width: Dimension, height: Dimension) -> Rect {
+        let console_height = ((height as f32 * Self::HEIGHT_RATIO) as Dimension).max(3);
+        Rect::new(
+            0,
+            height.saturating_sub(console_height),
+            width,
+            console_height,
+        )
+    }
+
+    /// `frame_height` is the height of the frame the console gets drawn over,
+    /// which PageUp/PageDown scroll a page of.
+    pub fn update(&mut self, input: &Input, frame_height: Dimension) {
+        if input.pressed_this_frame(Button::Console) {
+            self.visible = !self.visible;
+        }
+
+        if !self.visible {
+            self.generation = None;
+            return;
+        }
+
+        if input.pressed_this_frame(Button::ConsoleLevel) {
+            self.level = match self.level {
+                LevelFilter::Trace => LevelFilter::Debug,
+                LevelFilter::Debug => LevelFilter::Info,
+                LevelFilter::Info => LevelFilter::Warn,
+                LevelFilter::Warn => LevelFilter::Error,
+                LevelFilter::Error | LevelFilter::Off => LevelFilter::Trace,
+            };
+            self.scroll = 0;
+            self.generation = None;
+        }
+
+        if input.pressed_this_frame(Button::ConsoleTarget) {
+            self.target = self.next_target();
+            self.scroll = 0;
+            self.generation = None;
+        }
+
+        // only what was logged since the last update needs filtering, unless
+        // the filter changed and everything has to be gone through again
+        let mut generation = self.generation.unwrap_or_else(|| {
+            self.records.clear();
+            0
+        });
+        if self.logger.generation() != generation {
+            let new: Vec<_> = self.logger.with_records_since(&mut generation, |records| {
+                records
+                    .filter(|record| self.shows(record))
+                    .cloned()
+                    .collect()
+            });
+            self.records.extend(new);
+            let dropped = self.records.len().saturating_sub(Logger::CAPACITY);
+            self.records.drain(..dropped);
+        }
+        self.generation = Some(generation);
+
+        let page = Self::rect(0, frame_height).height.saturating_sub(2).max(1) as usize;
+        if input.pressed_this_frame(Button::PageUp) {
+            self.scroll += page;
+        }
+        if input.pressed_this_frame(Button::PageDown) {
+            self.scroll = self.scroll.saturating_sub(page);
+        }
+        self.scroll = self.scroll.min(self.records.len().saturating_sub(1));
+    }
+
+    fn shows(&self, record: &LogRecord) -> bool {
+        record.level <= self.level
+            && self
+                .target
+                .as_ref()
+                .is_none_or(|target| record.target.starts_with(target.as_str()))
+    }
+
+    /// Cycles through every target seen so far, then back to showing all of them.
+    fn next_target(&self) -> Option<String> {
+        self.logger.with_records(|records| {
+            records
+                .map(|record| record.target.as_str())
+                .filter(|target| {
+                    self.target
+                        .as_deref()
+                        .is_none_or(|current| *target > current)
+                })
+                .min()
+                .map(str::to_string)
+        })
+    }
+
+    fn level_color(record: &LogRecord) -> Color {
+        match record.level {
+            log::Level::Error => Color::Red,
+            log::Level::Warn => Color::Yellow,
+            log::Level::Info => Color::Green,
+            log::Level::Debug => Color::Cyan,
+            log::Level::Trace => Color::DarkGrey,
+        }
+    }
+}
+
+impl Drawable for LogConsole {
+    fn draw(&self, _camera: &Camera, renderer: &mut Renderer) {
+        if !self.visible {
+            return;
+        }
+
+        let rect = Self::rect(renderer.width(), renderer.height());
+        let lines = rect.height.saturating_sub(2) as usize;
+
+        let end = self.records.len() - self.scroll.min(self.records.len());
+        let start = end.saturating_sub(lines);
+        let shown = if start == end {
+            format!("0 of {}", self.records.len())
+        } else {
+            format!("{}-{} of {}", start + 1, end, self.records.len())
+        };
+
+        let panel = Panel::new(rect, BorderStyle::Single)
+            .fill(Cell::BLANK)
+            .title(format!(
+                "log  level: {}  target: {}",
+                self.level.as_str().to_lowercase(),
+                self.target.as_deref().unwrap_or("all"),
+            ))
+            .footer(shown);
+
+        panel.paint_with(renderer, |renderer, inner| {
+            for (record, y) in self.records.range(start..end).zip(inner.y..inner.bottom()) {
+                let style = Cell::BLANK.fg(Self::level_color(record));
+                let spans = [Span::new(record.to_string(), style)];
+                renderer.print_styled(inner.x, y, Align::Left, &spans);
+            }
+        });
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Hud
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+/// Simulation time. Only advances in fixed steps, so everything reading it
+/// agrees on "now" and stays deterministic no matter how fast frames are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clock {
+    now: Duration,
+    delta: Duration,
+}
+
+impl Clock {
+    pub fn new(delta: Duration) -> Self {
+        Self {
+            now: Duration::ZERO,
+            delta,
+        }
+    }
+
+    /// Simulation time elapsed since the clock started.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Length of one step.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Simulation time elapsed since `since`, which should be an earlier `now`.
+    pub fn since(&self, since: Duration) -> Duration {
+        self.now.saturating_sub(since)
+    }
+}
+
+/// Runs the simulation at a fixed step and rendering at its own rate. Each pass
+/// of the game loop calls `tick`, then `step` until it returns `None`, then
+/// `render`, then `sleep`.
+pub struct GameLoop {
+    clock: Clock,
+    render_interval: Duration,
+    accumulator: Duration,
+    last_tick: Instant,
+    next_render: Instant,
+    report_start: Instant,
+    steps: u32,
+    renders: u32,
+    update_rate: f64,
+    render_rate: f64,
+}
+
+impl GameLoop {
+    /// Real time beyond this is dropped instead of simulated, so a stall
+    /// (e.g. a suspended process) doesn't cause a long burst of catch-up steps.
+    const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+
+    const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new(step: Duration, render_rate: f32) -> Self {
+        let now = Instant::now();
+        Self {
+            clock: Clock::new(step),
+            render_interval: Duration::from_secs_f32(1.0 / render_rate),
+            accumulator: Duration::ZERO,
+            last_tick: now,
+            next_render: now,
+            report_start: now,
+            steps: 0,
+            renders: 0,
+            update_rate: 0.0,
+            render_rate: 0.0,
+        }
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.clock
+    }
+
+    /// Banks the real time passed since the last tick. Call once per pass of the loop.
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        self.accumulator += (now - self.last_tick).min(Self::MAX_FRAME_TIME);
+        self.last_tick = now;
+
+        let report_time = now - self.report_start;
+        if report_time >= Self::REPORT_INTERVAL {
+            self.update_rate = self.steps as f64 / report_time.as_secs_f64();
+            self.render_rate = self.renders as f64 / report_time.as_secs_f64();
+            log::debug!(
+                "{:.1} steps/s, {:.1} frames/s",
+                self.update_rate,
+                self.render_rate
+            );
+            self.steps = 0;
+            self.renders = 0;
+            self.report_start = now;
+        }
+    }
+
+    /// Advances the clock by one step if enough real time has been banked.
+    pub fn step(&mut self) -> Option<Clock> {
+        let delta = self.clock.delta;
+        if self.accumulator < delta {
+            return None;
+        }
+
+        self.accumulator -= delta;
+        self.clock.now += delta;
+        self.steps += 1;
+        Some(self.clock)
+    }
+
+    /// Takes back the clock advance of the step just taken, for a step that ended
+    /// up not simulating anything, e.g. while paused. Otherwise the paused time
+    /// would show up as a jump in `Clock::now` once the game carries on.
+    pub fn skip_step(&mut self) {
+        self.clock.now = self.clock.now.saturating_sub(self.clock.delta);
+    }
+
+    /// Whether a frame is due. If so, returns how far between the last step and
+    /// the next one it is, in `[0, 1)`, for interpolating what gets drawn.
+    pub fn render(&mut self) -> Option<f32> {
+        let now = Instant::now();
+        if now < self.next_render {
+            return None;
+        }
+
+        // don't try to make up for missed frames, just get back on schedule
+        self.next_render = (self.next_render + self.render_interval).max(now);
+        self.renders += 1;
+        Some(self.accumulator.as_secs_f32() / self.clock.delta.as_secs_f32())
+    }
+
+    /// Sleeps until the next step or frame is due, whichever comes first.
+    pub fn sleep(&self) {
+        let next_step = self.last_tick + self.clock.delta.saturating_sub(self.accumulator);
+        let wake = next_step.min(self.next_render);
+        // use spin_sleep since native sleep is often too slow / low res
+        spin_sleep::sleep(wake.saturating_duration_since(Instant::now()));
+    }
+
+    /// Steps per second over the last report interval.
+    pub fn update_rate(&self) -> f64 {
+        self.update_rate
+    }
+
+    /// Frames per second over the last report interval.
+    pub fn render_rate(&self) -> f64 {
+        self.render_rate
+    }
+}
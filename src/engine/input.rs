@@ -1,19 +1,12 @@
-use std::{collections::HashMap, time::Duration};
-
-use crossterm::{
-    event::{
-        poll, read, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent,
-        KeyEventKind, KeyModifiers, KeyboardEnhancementFlags, MouseButton, MouseEvent,
-        MouseEventKind, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
-    },
-    execute, queue, terminal,
-};
+use std::{collections::HashMap, path::Path, time::Duration};
 
-use crate::engine::{Camera, Pos, ScreenPos};
+use crate::engine::{
+    Bindings, Camera, Dimension, InputRecorder, InputReplay, Pos, ScreenPos, ScriptedInput,
+    TerminalInput, REPLAY_BUTTONS,
+};
 
-/// Only LeftMouse and RightMouse actually support release events,
-/// at least in WSL + Windows Terminal. Keyboard keys with just toggle
-/// between pressed this frame and pressed after their first press.
+/// Everything but the mouse buttons is set off through `Bindings`, those always
+/// follow the physical mouse buttons, e.g. for clicking UI.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Button {
     Quit,
@@ -21,51 +14,26 @@ pub enum Button {
     Down,
     Left,
     Right,
+    Jump,
+    Grapple,
     Reset,
     Space,
+    Record,
+    Screenshot,
+    Console,
+    Map,
+    ConsoleLevel,
+    ConsoleTarget,
+    PageUp,
+    PageDown,
     LeftMouse,
     RightMouse,
+    MiddleMouse,
 }
 
 impl Button {
-    fn from_key_event(event: &KeyEvent) -> Option<Self> {
-        match event.code {
-            KeyCode::Esc => Some(Self::Quit),
-            KeyCode::Char(c) => match c {
-                'c' => {
-                    if event.modifiers == KeyModifiers::CONTROL {
-                        Some(Self::Quit)
-                    } else {
-                        None
-                    }
-                }
-                'w' => Some(Self::Up),
-                'a' => Some(Self::Left),
-                's' => Some(Self::Down),
-                'd' => Some(Self::Right),
-                'r' => Some(Self::Reset),
-                ' ' => Some(Self::Space),
-                _ => None,
-            },
-            KeyCode::Up => Some(Self::Up),
-            KeyCode::Down => Some(Self::Down),
-            KeyCode::Left => Some(Self::Left),
-            KeyCode::Right => Some(Self::Right),
-            _ => None,
-        }
-    }
-
-    fn from_mouse_event(event: &MouseEvent) -> Option<Self> {
-        match event.kind {
-            MouseEventKind::Up(mouse_button) | MouseEventKind::Down(mouse_button) => {
-                match mouse_button {
-                    MouseButton::Left => Some(Self::LeftMouse),
-                    MouseButton::Right => Some(Self::RightMouse),
-                    MouseButton::Middle => None,
-                }
-            }
-            _ => None,
-        }
+    fn is_mouse(self) -> bool {
+        matches!(self, Self::LeftMouse | Self::RightMouse | Self::MiddleMouse)
     }
 }
 
@@ -77,27 +45,126 @@ pub enum ButtonState {
     Released,
 }
 
+/// Something an `InputSource` reports happened since the last update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    Press(Button),
+    Release(Button),
+    /// Puts a button in a state outright, e.g. when replaying.
+    Set(Button, ButtonState),
+    /// The mouse moved over this terminal cell. Its world position follows
+    /// from the camera `Input::update` is given.
+    MouseMoved(ScreenPos),
+    /// The mouse is over this world position, whatever the camera shows.
+    MouseAt(Pos),
+    /// The wheel turned this many notches, down and right being positive.
+    Scroll(ScreenPos),
+    /// The terminal is now this many `(columns, rows)`.
+    Resized(Dimension, Dimension),
+}
+
+/// Where `Input` gets its events from: the terminal, a script or a replay.
+pub trait InputSource {
+    /// Adds whatever happened since the last call, in order. Called once per update.
+    fn poll(&mut self, events: &mut Vec<InputEvent>) -> std::io::Result<()>;
+
+    /// Whether the source has run out, like a script past its last step.
+    fn finished(&self) -> bool {
+        false
+    }
+}
+
+/// A mouse button held down while the mouse moves, e.g. to pan around.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drag {
+    pub button: Button,
+    /// Terminal cell the button went down on.
+    pub origin: ScreenPos,
+}
+
 pub struct Input {
     pub mouse_pos: Pos,
     pub mouse_screen_pos: ScreenPos,
     state: HashMap<Button, ButtonState>,
+    resized: Option<(Dimension, Dimension)>,
+    mouse_delta: ScreenPos,
+    mouse_moved: bool,
+    scroll: ScreenPos,
+    /// The first mouse button still held, and whether the mouse moved since.
+    held_mouse: Option<(Drag, bool)>,
+    source: Box<dyn InputSource>,
+    recorder: Option<InputRecorder>,
+    replay: Option<InputReplay>,
 }
 
 impl Input {
     pub fn new() -> std::io::Result<Self> {
-        terminal::enable_raw_mode()?;
+        Self::with_bindings(Bindings::default())
+    }
 
-        execute!(
-            std::io::stdout(),
-            EnableMouseCapture,
-            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::all())
-        )?;
+    /// Reads the terminal, see `TerminalInput`.
+    pub fn with_bindings(bindings: Bindings) -> std::io::Result<Self> {
+        Ok(Self::with_source(Box::new(TerminalInput::new(bindings)?)))
+    }
+
+    /// Plays `script` instead of reading the terminal, so the game can be
+    /// driven from tests.
+    pub fn scripted(script: ScriptedInput) -> Self {
+        Self::with_source(Box::new(script))
+    }
 
-        Ok(Self {
+    /// Takes input from anywhere, e.g. a terminal, a script or a replay.
+    pub fn with_source(source: Box<dyn InputSource>) -> Self {
+        Self {
             state: HashMap::new(),
             mouse_pos: Pos::ZERO,
             mouse_screen_pos: ScreenPos::ZERO,
-        })
+            resized: None,
+            mouse_delta: ScreenPos::ZERO,
+            mouse_moved: false,
+            scroll: ScreenPos::ZERO,
+            held_mouse: None,
+            source,
+            recorder: None,
+            replay: None,
+        }
+    }
+
+    /// Whether the source has run out, see `InputSource::finished`.
+    pub fn finished(&self) -> bool {
+        self.source.finished()
+    }
+
+    /// Saves the gameplay input of every following `update` to a replay file.
+    /// `step` is the update interval and `stage` tells apart what's played on,
+    /// both of which the replay has to be played back with.
+    pub fn record_to(&mut self, path: &Path, step: Duration, stage: u64) -> std::io::Result<()> {
+        self.recorder = Some(InputRecorder::create(path, step, stage)?);
+        Ok(())
+    }
+
+    pub fn recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Leaves the step of the last `update` out of the recording, for a step that
+    /// didn't simulate anything, e.g. while paused.
+    pub fn skip_step(&mut self) {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.skip();
+        }
+    }
+
+    /// Drives the gameplay buttons and mouse from a replay file on top of the
+    /// source, until it runs out. Started along with the run it was recorded
+    /// in, the run plays out exactly the same.
+    pub fn replay_from(&mut self, path: &Path, step: Duration, stage: u64) -> std::io::Result<()> {
+        self.replay = Some(InputReplay::open(path, step, stage)?);
+        Ok(())
+    }
+
+    pub fn replaying(&self) -> bool {
+        self.replay.is_some()
     }
 
     pub fn button_state(&self, button: Button) -> ButtonState {
@@ -126,7 +193,38 @@ impl Input {
         matches!(self.button_state(button), ButtonState::ReleasedThisFrame)
     }
 
+    /// The new terminal `(columns, rows)` if it was resized this frame.
+    pub fn resized(&self) -> Option<(Dimension, Dimension)> {
+        self.resized
+    }
+
+    /// Whether the mouse moved this frame, over the terminal or the world.
+    pub fn mouse_moved(&self) -> bool {
+        self.mouse_moved
+    }
+
+    /// How many cells the mouse moved across the terminal this frame.
+    pub fn mouse_delta(&self) -> ScreenPos {
+        self.mouse_delta
+    }
+
+    /// Notches the wheel turned this frame, down and right being positive.
+    pub fn scroll(&self) -> ScreenPos {
+        self.scroll
+    }
+
+    /// The drag going on, once the mouse has moved with a button held.
+    pub fn drag(&self) -> Option<Drag> {
+        self.held_mouse
+            .and_then(|(drag, moved)| moved.then_some(drag))
+    }
+
     pub fn update(&mut self, camera: &Camera) -> std::io::Result<()> {
+        self.resized = None;
+        self.mouse_delta = ScreenPos::ZERO;
+        self.mouse_moved = false;
+        self.scroll = ScreenPos::ZERO;
+
         for button_state in self.state.values_mut() {
             match button_state {
                 ButtonState::PressedThisFrame => *button_state = ButtonState::Pressed,
@@ -134,53 +232,69 @@ impl Input {
                 _ => (),
             }
         }
+        // the replay goes last, so it has the final say over the gameplay input
+        let mut events = Vec::new();
+        self.source.poll(&mut events)?;
+        if let Some(replay) = &mut self.replay {
+            replay.poll(&mut events)?;
+            if replay.finished() {
+                log::info!("replay finished after {} steps", replay.frames());
+                self.replay = None;
+            }
+        }
 
-        while poll(Duration::ZERO)? {
-            match read()? {
-                Event::Key(key_event) => {
-                    if let Some(button) = Button::from_key_event(&key_event) {
-                        match key_event.kind {
-                            KeyEventKind::Press => {
-                                self.state.insert(button, ButtonState::PressedThisFrame);
-                            }
-                            KeyEventKind::Release => {
-                                self.state.insert(button, ButtonState::ReleasedThisFrame);
-                            }
-                            _ => (),
-                        }
-                    }
-                }
-                Event::Mouse(mouse_event) => {
-                    self.mouse_screen_pos =
-                        ScreenPos::new(mouse_event.column.into(), mouse_event.row.into());
-                    self.mouse_pos = camera.pos + (self.mouse_screen_pos - camera.frame_pos).into();
-
-                    if let Some(button) = Button::from_mouse_event(&mouse_event) {
-                        match mouse_event.kind {
-                            MouseEventKind::Down(_) => {
-                                self.state.insert(button, ButtonState::PressedThisFrame);
-                            }
-                            MouseEventKind::Up(_) => {
-                                self.state.insert(button, ButtonState::ReleasedThisFrame);
-                            }
-                            _ => (),
-                        }
+        for event in events {
+            self.apply(event, camera);
+        }
+
+        let buttons = REPLAY_BUTTONS.map(|button| self.button_state(button));
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(buttons, self.mouse_pos)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, event: InputEvent, camera: &Camera) {
+        match event {
+            InputEvent::Press(button) => self.set_state(button, ButtonState::PressedThisFrame),
+            InputEvent::Release(button) => self.set_state(button, ButtonState::ReleasedThisFrame),
+            InputEvent::Set(button, state) => self.set_state(button, state),
+            InputEvent::MouseMoved(screen_pos) => {
+                if screen_pos != self.mouse_screen_pos {
+                    self.mouse_delta += screen_pos - self.mouse_screen_pos;
+                    self.mouse_moved = true;
+                    if let Some((_, moved)) = &mut self.held_mouse {
+                        *moved = true;
                     }
                 }
-                _ => (),
+                self.mouse_screen_pos = screen_pos;
+                self.mouse_pos = camera.pos + (screen_pos - camera.frame_pos).into();
             }
+            InputEvent::MouseAt(pos) => {
+                self.mouse_moved |= pos != self.mouse_pos;
+                self.mouse_pos = pos;
+            }
+            InputEvent::Scroll(notches) => self.scroll += notches,
+            InputEvent::Resized(columns, rows) => self.resized = Some((columns, rows)),
         }
-        Ok(())
     }
-}
 
-impl Drop for Input {
-    fn drop(&mut self) {
-        let _ = terminal::disable_raw_mode();
-        let _ = queue!(
-            std::io::stdout(),
-            DisableMouseCapture,
-            PopKeyboardEnhancementFlags
-        );
+    /// Also keeps track of which mouse button a drag would be with.
+    fn set_state(&mut self, button: Button, state: ButtonState) {
+        if button.is_mouse() {
+            match (state, self.held_mouse) {
+                (ButtonState::PressedThisFrame, None) => {
+                    let origin = self.mouse_screen_pos;
+                    self.held_mouse = Some((Drag { button, origin }, false));
+                }
+                (ButtonState::ReleasedThisFrame | ButtonState::Released, Some((drag, _)))
+                    if drag.button == button =>
+                {
+                    self.held_mouse = None;
+                }
+                _ => (),
+            }
+        }
+        self.state.insert(button, state);
     }
 }
@@ -1,44 +1,330 @@
-use std::sync::Mutex;
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
 
-use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use log::{Level, LevelFilter, Log, Metadata, Record};
 
-struct LoggerInner {
-    buffer: Mutex<Vec<String>>,
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub level: Level,
+    pub target: String,
+    /// Time since the logger was set up.
+    pub time: Duration,
+    pub msg: String,
 }
 
-impl LoggerInner {
-    fn new() -> Self {
+impl std::fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>8.3} {:<5} {}: {}",
+            self.time.as_secs_f32(),
+            self.level,
+            self.target,
+            self.msg
+        )
+    }
+}
+
+/// Per-module log levels, parsed from specs like `info,splat::game=debug`.
+/// The most specific matching module wins.
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    default: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    pub fn new(default: LevelFilter) -> Self {
         Self {
-            buffer: Mutex::new(Vec::new()),
+            default,
+            targets: Vec::new(),
+        }
+    }
+
+    pub fn parse(spec: &str) -> std::io::Result<Self> {
+        let mut filter = Self::new(LevelFilter::Error);
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => filter
+                    .targets
+                    .push((target.trim().to_string(), Self::parse_level(level)?)),
+                None => match Self::parse_level(directive) {
+                    Ok(level) => filter.default = level,
+                    // a bare module name turns everything on for it
+                    Err(_) => filter
+                        .targets
+                        .push((directive.to_string(), LevelFilter::Trace)),
+                },
+            }
+        }
+
+        Ok(filter)
+    }
+
+    fn parse_level(level: &str) -> std::io::Result<LevelFilter> {
+        level.trim().parse().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("unknown log level {level:?}"),
+            )
+        })
+    }
+
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(module, _)| {
+                target == module
+                    || target
+                        .strip_prefix(module.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    /// The most verbose level anything could be logged at.
+    pub fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+pub struct LogConfig {
+    pub filter: LogFilter,
+    /// Also append every record to this file, if set.
+    pub file: Option<PathBuf>,
+    /// Size at which the log file gets rotated out.
+    pub max_file_size: u64,
+    /// How many rotated files to keep, with `<file>.1` being the newest.
+    pub max_rotated: usize,
+}
+
+impl LogConfig {
+    pub const FILTER_VAR: &'static str = "SPLAT_LOG";
+    pub const FILE_VAR: &'static str = "SPLAT_LOG_FILE";
+
+    /// The defaults, overridden by `SPLAT_LOG` (a filter spec) and `SPLAT_LOG_FILE`.
+    pub fn from_env() -> std::io::Result<Self> {
+        let mut config = Self::default();
+        if let Ok(spec) = std::env::var(Self::FILTER_VAR) {
+            config.filter = LogFilter::parse(&spec)?;
+        }
+        if let Some(file) = std::env::var_os(Self::FILE_VAR) {
+            config.file = Some(file.into());
+        }
+        Ok(config)
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            filter: LogFilter::new(LevelFilter::Debug),
+            file: None,
+            max_file_size: 1024 * 1024,
+            max_rotated: 3,
+        }
+    }
+}
+
+/// Appends records to a file, rotating it once it grows past the size limit.
+/// Every record is written straight through, so nothing is lost on a crash.
+struct FileSink {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_rotated: usize,
+}
+
+impl FileSink {
+    fn open(path: &Path, max_size: u64, max_rotated: usize) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_size,
+            max_rotated,
+        })
+    }
+
+    fn write(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > self.max_size {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{n}"));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        if self.max_rotated == 0 {
+            self.file.set_len(0)?;
+        } else {
+            for n in (1..self.max_rotated).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    std::fs::rename(from, self.rotated_path(n + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = File::create(&self.path)?;
         }
+        self.size = 0;
+        Ok(())
+    }
+}
+
+struct LoggerInner {
+    buffer: Mutex<VecDeque<LogRecord>>,
+    /// How many records have been buffered so far, dropped ones included.
+    generation: AtomicU64,
+    start_time: Instant,
+    filter: LogFilter,
+    file: Option<Mutex<FileSink>>,
+}
+
+impl LoggerInner {
+    fn new(config: LogConfig) -> std::io::Result<Self> {
+        let file = match &config.file {
+            Some(path) => Some(Mutex::new(FileSink::open(
+                path,
+                config.max_file_size,
+                config.max_rotated,
+            )?)),
+            None => None,
+        };
+
+        Ok(Self {
+            buffer: Mutex::new(VecDeque::with_capacity(Logger::CAPACITY)),
+            generation: AtomicU64::new(0),
+            start_time: Instant::now(),
+            filter: config.filter,
+            file,
+        })
     }
 }
 
 impl Log for LoggerInner {
-    fn enabled(&self, _metadata: &Metadata) -> bool {
-        true
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
     }
 
     fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let record = LogRecord {
+            level: record.level(),
+            target: record.target().to_string(),
+            time: self.start_time.elapsed(),
+            msg: record.args().to_string(),
+        };
+
+        if let Some(Ok(mut file)) = self.file.as_ref().map(Mutex::lock) {
+            // nowhere left to report a failing log file
+            let _ = file.write(&record.to_string());
+        }
+
         if let Ok(mut buf) = self.buffer.lock() {
-            buf.push(record.args().to_string());
+            if buf.len() == Logger::CAPACITY {
+                buf.pop_front();
+            }
+            buf.push_back(record);
+            self.generation.fetch_add(1, Ordering::Relaxed);
         }
     }
 
-    fn flush(&self) {}
+    fn flush(&self) {
+        if let Some(Ok(mut file)) = self.file.as_ref().map(Mutex::lock) {
+            let _ = file.file.flush();
+        }
+    }
 }
 
 pub struct Logger(LoggerInner);
 
 impl Logger {
-    pub fn setup() -> Result<&'static Self, SetLoggerError> {
-        let logger = Box::leak(Box::new(Self(LoggerInner::new())));
-        log::set_logger(&logger.0)?;
-        log::set_max_level(LevelFilter::Debug);
+    /// How many records are kept before the oldest get dropped.
+    pub const CAPACITY: usize = 1000;
+
+    pub fn setup() -> std::io::Result<&'static Self> {
+        Self::setup_with(LogConfig::default())
+    }
+
+    pub fn setup_with(config: LogConfig) -> std::io::Result<&'static Self> {
+        let logger = Box::leak(Box::new(Self(LoggerInner::new(config)?)));
+        log::set_logger(&logger.0).map_err(|err| std::io::Error::other(err.to_string()))?;
+        log::set_max_level(logger.0.filter.max_level());
         Ok(logger)
     }
 
-    pub fn drain(&self) -> Vec<String> {
+    /// A copy of the buffered records, oldest first.
+    pub fn records(&self) -> Vec<LogRecord> {
+        if let Ok(buf) = self.0.buffer.lock() {
+            buf.iter().cloned().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Runs `f` over the buffered records, oldest first, without copying them.
+    /// Holds the buffer locked meanwhile, so `f` mustn't log.
+    pub fn with_records<R>(&self, f: impl FnOnce(&mut dyn Iterator<Item = &LogRecord>) -> R) -> R {
+        match self.0.buffer.lock() {
+            Ok(buf) => f(&mut buf.iter()),
+            Err(_) => f(&mut std::iter::empty()),
+        }
+    }
+
+    /// Runs `f` over the buffered records logged since `generation`, oldest first,
+    /// then moves `generation` past them. Starting from 0 covers every record.
+    /// Holds the buffer locked meanwhile, so `f` mustn't log.
+    pub fn with_records_since<R>(
+        &self,
+        generation: &mut u64,
+        f: impl FnOnce(&mut dyn Iterator<Item = &LogRecord>) -> R,
+    ) -> R {
+        match self.0.buffer.lock() {
+            Ok(buf) => {
+                let current = self.generation();
+                let new = (current - *generation).min(buf.len() as u64) as usize;
+                *generation = current;
+                f(&mut buf.range(buf.len() - new..))
+            }
+            Err(_) => f(&mut std::iter::empty()),
+        }
+    }
+
+    /// How many records have been logged so far, including ones since dropped
+    /// or drained, so readers can tell when there's something new.
+    pub fn generation(&self) -> u64 {
+        self.0.generation.load(Ordering::Relaxed)
+    }
+
+    pub fn drain(&self) -> Vec<LogRecord> {
         if let Ok(mut buf) = self.0.buffer.lock() {
             buf.drain(..).collect()
         } else {
@@ -55,3 +341,57 @@ macro_rules! dbg {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_uses_most_specific_module() {
+        let filter = LogFilter::parse("info,splat::game=debug").unwrap();
+        assert_eq!(filter.level_for("splat"), LevelFilter::Info);
+        assert_eq!(filter.level_for("splat::engine::render"), LevelFilter::Info);
+        assert_eq!(filter.level_for("splat::game"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("splat::game::player"), LevelFilter::Debug);
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn filter_turns_on_bare_module() {
+        let filter = LogFilter::parse("splat::engine").unwrap();
+        assert_eq!(filter.level_for("splat::engine::input"), LevelFilter::Trace);
+        assert_eq!(filter.level_for("splat::game"), LevelFilter::Error);
+        assert!(LogFilter::parse("splat=loud").is_err());
+    }
+
+    #[test]
+    fn filter_needs_whole_module() {
+        let filter = LogFilter::parse("warn,splat::game=trace").unwrap();
+        assert_eq!(filter.level_for("splat::gamepad"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("splat::gamepad::stick"), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn rotation_drops_oldest_file() {
+        let dir = std::env::temp_dir().join(format!("splat-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("splat.log");
+
+        // room for a single line per file
+        let mut sink = FileSink::open(&path, 7, 2).unwrap();
+        for n in 0..5 {
+            sink.write(&format!("line {n}")).unwrap();
+        }
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap();
+        let contents = [
+            read(path.clone()),
+            read(sink.rotated_path(1)),
+            read(sink.rotated_path(2)),
+        ];
+        let oldest_kept = sink.rotated_path(3).exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(contents, ["line 4\n", "line 3\n", "line 2\n"]);
+        assert!(!oldest_kept);
+    }
+}
@@ -2,20 +2,68 @@ mod pos;
 mod pos_ops;
 pub use pos::*;
 
+mod cell;
+pub use cell::*;
+
+mod backend;
+pub use backend::*;
+
+mod cast;
+pub use cast::*;
+
+mod screenshot;
+pub use screenshot::*;
+
 mod render;
 pub use render::*;
 
+mod text;
+pub use text::*;
+
+mod panel;
+pub use panel::*;
+
 mod camera;
 pub use camera::*;
 
+mod camera_effects;
+pub use camera_effects::*;
+
+mod viewport;
+pub use viewport::*;
+
+mod game_loop;
+pub use game_loop::*;
+
+mod session;
+pub use session::*;
+
 mod input;
 pub use input::*;
 
+mod terminal_input;
+pub use terminal_input::*;
+
+mod scripted_input;
+pub use scripted_input::*;
+
+mod bindings;
+pub use bindings::*;
+
+mod replay;
+pub use replay::*;
+
 mod ray;
 pub use ray::*;
 
+mod braille;
+pub use braille::*;
+
 mod animation;
 pub use animation::*;
 
 mod logger;
 pub use logger::*;
+
+mod console;
+pub use console::*;
@@ -0,0 +1,138 @@
+use super::{Align, Cell, Rect, Renderer, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorderStyle {
+    Single,
+    Heavy,
+    Double,
+    Rounded,
+    Ascii,
+}
+
+impl BorderStyle {
+    /// Corners clockwise from the top left, then the horizontal and vertical edges.
+    fn glyphs(&self) -> [char; 6] {
+        match self {
+            Self::Single => ['┌', '┐', '┘', '└', '─', '│'],
+            Self::Heavy => ['┏', '┓', '┛', '┗', '━', '┃'],
+            Self::Double => ['╔', '╗', '╝', '╚', '═', '║'],
+            Self::Rounded => ['╭', '╮', '╯', '╰', '─', '│'],
+            Self::Ascii => ['+', '+', '+', '+', '-', '|'],
+        }
+    }
+}
+
+/// A bordered box around an area of the frame, with an optional title set into
+/// the top edge and footer set into the bottom one.
+#[derive(Debug, Clone)]
+pub struct Panel {
+    pub rect: Rect,
+    pub border: BorderStyle,
+    /// Colors and attributes for the border, title and footer.
+    pub style: Cell,
+    /// Painted over the inside of the panel first, if set.
+    pub fill: Option<Cell>,
+    pub title: Option<String>,
+    pub footer: Option<String>,
+}
+
+impl Panel {
+    pub fn new(rect: Rect, border: BorderStyle) -> Self {
+        Self {
+            rect,
+            border,
+            style: Cell::BLANK,
+            fill: None,
+            title: None,
+            footer: None,
+        }
+    }
+
+    pub fn style(mut self, style: Cell) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn fill(mut self, fill: Cell) -> Self {
+        self.fill = Some(fill);
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    /// The area inside the border.
+    pub fn inner(&self) -> Rect {
+        self.rect.inset(1)
+    }
+
+    pub fn paint(&self, renderer: &mut Renderer) {
+        let rect = self.rect;
+        if rect.width < 2 || rect.height < 2 {
+            return;
+        }
+
+        let [top_left, top_right, bottom_right, bottom_left, horizontal, vertical] =
+            self.border.glyphs().map(|glyph| Cell {
+                glyph,
+                ..self.style
+            });
+        let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+
+        renderer.paint(rect.x, rect.y, top_left);
+        renderer.paint(right, rect.y, top_right);
+        renderer.paint(right, bottom, bottom_right);
+        renderer.paint(rect.x, bottom, bottom_left);
+
+        for x in rect.x + 1..right {
+            renderer.paint(x, rect.y, horizontal);
+            renderer.paint(x, bottom, horizontal);
+        }
+
+        for y in rect.y + 1..bottom {
+            renderer.paint(rect.x, y, vertical);
+            renderer.paint(right, y, vertical);
+        }
+
+        if let Some(fill) = self.fill {
+            let inner = self.inner();
+            for y in inner.y..inner.bottom() {
+                for x in inner.x..inner.right() {
+                    renderer.paint(x, y, fill);
+                }
+            }
+        }
+
+        // keep at least one edge glyph between the labels and the corners
+        let label_area = Rect::new(
+            rect.x + 2,
+            rect.y,
+            rect.width.saturating_sub(4),
+            rect.height,
+        );
+        renderer.with_clip(label_area, |renderer| {
+            if let Some(title) = &self.title {
+                let spans = [Span::new(format!(" {title} "), self.style)];
+                renderer.print_styled(label_area.x, rect.y, Align::Left, &spans);
+            }
+            if let Some(footer) = &self.footer {
+                let spans = [Span::new(format!(" {footer} "), self.style)];
+                renderer.print_styled(label_area.right(), bottom, Align::Right, &spans);
+            }
+        });
+    }
+
+    /// Paints the panel, then runs `f` with paints clipped to the inside of it.
+    pub fn paint_with(&self, renderer: &mut Renderer, f: impl FnOnce(&mut Renderer, Rect)) {
+        self.paint(renderer);
+        let inner = self.inner();
+        renderer.with_clip(inner, |renderer| f(renderer, inner));
+    }
+}
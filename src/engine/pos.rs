@@ -51,6 +51,11 @@ impl Pos {
         }
     }
 
+    /// The point `t` of the way from `self` to `other`.
+    pub fn lerp(&self, other: Pos, t: Coord) -> Self {
+        *self + (other - *self).scale(t)
+    }
+
     pub fn transform_basis(&self, new_basis: Pos) -> Self {
         Self {
             x: self.x * new_basis.x + self.y * new_basis.y,
@@ -1,45 +1,145 @@
-use std::io::Write;
+use std::path::Path;
 
-use crossterm::{cursor, queue, style, terminal};
-
-use super::{Camera, Logger};
+use super::{
+    Backend, Camera, CastRecorder, Cell, MemoryBackend, ScreenshotFormat, TerminalBackend,
+    TerminalSession,
+};
 
 pub type Dimension = u16;
 
+/// An area of the frame, in screen cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: Dimension,
+    pub y: Dimension,
+    pub width: Dimension,
+    pub height: Dimension,
+}
+
+impl Rect {
+    pub const fn new(x: Dimension, y: Dimension, width: Dimension, height: Dimension) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// One past the rightmost column.
+    pub fn right(&self) -> Dimension {
+        self.x.saturating_add(self.width)
+    }
+
+    /// One past the bottom row.
+    pub fn bottom(&self) -> Dimension {
+        self.y.saturating_add(self.height)
+    }
+
+    pub fn contains(&self, x: Dimension, y: Dimension) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// The overlap of two rects, which may be empty.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Self {
+            x,
+            y,
+            width: self.right().min(other.right()).saturating_sub(x),
+            height: self.bottom().min(other.bottom()).saturating_sub(y),
+        }
+    }
+
+    /// Shrinks the rect by `margin` on every side.
+    pub fn inset(&self, margin: Dimension) -> Self {
+        Self {
+            x: self.x.saturating_add(margin),
+            y: self.y.saturating_add(margin),
+            width: self.width.saturating_sub(margin * 2),
+            height: self.height.saturating_sub(margin * 2),
+        }
+    }
+}
+
+/// Draw layers from back to front. Opaque cells on a higher layer hide anything
+/// below them, regardless of which was painted first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    Background,
+    Stage,
+    Entities,
+    Foreground,
+    Hud,
+}
+
+impl Layer {
+    pub const ALL: [Self; 5] = [
+        Self::Background,
+        Self::Stage,
+        Self::Entities,
+        Self::Foreground,
+        Self::Hud,
+    ];
+}
+
 pub struct Renderer {
     width: Dimension,
     height: Dimension,
-    frame: Vec<Vec<char>>,
-    stdout: std::io::Stdout,
-    logger: Option<&'static Logger>,
+    layers: Vec<Vec<Vec<Cell>>>,
+    layer: Layer,
+    clip: Option<Rect>,
+    alpha: f32,
+    frame: Vec<Vec<Cell>>,
+    front: Vec<Vec<Cell>>,
+    full_repaint: bool,
+    backend: Box<dyn Backend>,
+    recorder: Option<CastRecorder>,
 }
 
 impl Renderer {
-    const CLEAR_CHAR: char = ' ';
+    const CLEAR_CELL: Cell = Cell::BLANK;
+
+    /// Unchanged cells this close together get reprinted instead of skipped,
+    /// since a cursor move costs more bytes than a few glyphs.
+    const MAX_RUN_GAP: usize = 4;
+
+    pub fn new(width: Dimension, height: Dimension) -> std::io::Result<Self> {
+        Self::with_backend(width, height, Box::new(TerminalBackend::stdout()))
+    }
 
-    pub fn new(
+    /// Draws into an in-memory grid instead of the terminal. The returned backend
+    /// shares that grid, so it can be inspected after `render`.
+    pub fn headless(width: Dimension, height: Dimension) -> (Self, MemoryBackend) {
+        let backend = MemoryBackend::new(width, height);
+        let renderer = Self::with_backend(width, height, Box::new(backend.clone()))
+            .expect("memory backend setup is infallible");
+        (renderer, backend)
+    }
+
+    pub fn with_backend(
         width: Dimension,
         height: Dimension,
-        logger: Option<&'static Logger>,
+        mut backend: Box<dyn Backend>,
     ) -> std::io::Result<Self> {
-        let mut stdout = std::io::stdout();
-
-        queue!(
-            stdout,
-            cursor::Hide,
-            terminal::Clear(terminal::ClearType::All)
-        )?;
-
-        if let Some(logger) = logger.as_ref() {
-            logger.drain();
-        }
+        backend.setup()?;
 
         Ok(Self {
             width,
             height,
-            frame: vec![vec![Self::CLEAR_CHAR; width as usize]; height as usize],
-            stdout,
-            logger,
+            layers: vec![
+                vec![vec![Cell::TRANSPARENT; width as usize]; height as usize];
+                Layer::ALL.len()
+            ],
+            layer: Layer::Entities,
+            clip: None,
+            alpha: 0.0,
+            frame: vec![vec![Self::CLEAR_CELL; width as usize]; height as usize],
+            front: vec![vec![Self::CLEAR_CELL; width as usize]; height as usize],
+            full_repaint: true,
+            backend,
+            recorder: None,
         })
     }
 
@@ -51,58 +151,334 @@ impl Renderer {
         self.height
     }
 
-    pub fn paint(&mut self, frame_x: Dimension, frame_y: Dimension, dot: char) {
-        if frame_x < self.width && frame_y < self.height {
-            self.frame[frame_y as usize][frame_x as usize] = dot;
+    /// Reallocates the buffers for a new size and repaints everything on the next render.
+    pub fn resize(&mut self, width: Dimension, height: Dimension) {
+        self.width = width;
+        self.height = height;
+        self.layers =
+            vec![vec![vec![Cell::TRANSPARENT; width as usize]; height as usize]; Layer::ALL.len()];
+        self.frame = vec![vec![Self::CLEAR_CELL; width as usize]; height as usize];
+        self.front = vec![vec![Self::CLEAR_CELL; width as usize]; height as usize];
+        self.invalidate();
+
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = recorder.resize(width, height) {
+                log::warn!("stopped recording: {err}");
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// How far the frame being drawn is between the last two simulation steps,
+    /// for drawables to interpolate their positions with.
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.alpha = alpha;
+    }
+
+    pub fn layer(&self) -> Layer {
+        self.layer
+    }
+
+    /// Sets the layer that subsequent paints land on.
+    pub fn set_layer(&mut self, layer: Layer) {
+        self.layer = layer;
+    }
+
+    /// Runs `f` with paints going to `layer`, then switches back.
+    pub fn with_layer(&mut self, layer: Layer, f: impl FnOnce(&mut Self)) {
+        let prev = std::mem::replace(&mut self.layer, layer);
+        f(self);
+        self.layer = prev;
+    }
+
+    /// Runs `f` with paints outside of `rect` discarded. Nested clips intersect.
+    pub fn with_clip(&mut self, rect: Rect, f: impl FnOnce(&mut Self)) {
+        let clip = self.clip.map_or(rect, |clip| clip.intersect(&rect));
+        let prev = self.clip.replace(clip);
+        f(self);
+        self.clip = prev;
+    }
+
+    /// Draws `drawable` on its own layer.
+    pub fn draw(&mut self, drawable: &(impl Drawable + ?Sized), camera: &Camera) {
+        self.with_layer(drawable.layer(), |renderer| drawable.draw(camera, renderer));
+    }
+
+    pub fn paint(&mut self, frame_x: Dimension, frame_y: Dimension, dot: impl Into<Cell>) {
+        let dot = dot.into();
+        if frame_x < self.width
+            && frame_y < self.height
+            && !dot.is_transparent()
+            && self.clip.is_none_or(|clip| clip.contains(frame_x, frame_y))
+        {
+            self.layers[self.layer as usize][frame_y as usize][frame_x as usize] = dot;
         }
     }
 
     pub fn clear(&mut self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                self.frame[y as usize][x as usize] = Self::CLEAR_CHAR;
+        for cell in self.layers.iter_mut().flatten().flatten() {
+            *cell = Cell::TRANSPARENT;
+        }
+    }
+
+    /// Clears `rect` on every layer, so whatever was painted there is gone
+    /// no matter which layer anything drawn over it lands on.
+    pub fn erase(&mut self, rect: Rect) {
+        let rect = rect.intersect(&Rect::new(0, 0, self.width, self.height));
+        for layer in &mut self.layers {
+            for row in &mut layer[rect.y as usize..rect.bottom() as usize] {
+                row[rect.x as usize..rect.right() as usize].fill(Cell::TRANSPARENT);
             }
         }
     }
 
+    /// Collapses the layers into `frame`, taking the topmost opaque cell at each spot.
+    fn flatten(&mut self) {
+        for (y, row) in self.frame.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self
+                    .layers
+                    .iter()
+                    .rev()
+                    .map(|layer| layer[y][x])
+                    .find(|dot| !dot.is_transparent())
+                    .unwrap_or(Self::CLEAR_CELL);
+            }
+        }
+    }
+
+    /// The frame as of the last render, with all layers flattened.
+    pub fn frame(&self) -> &[Vec<Cell>] {
+        &self.frame
+    }
+
+    /// Saves the last rendered frame, in the format matching the extension of `path`:
+    /// `.txt`, `.ans` or `.html`.
+    pub fn screenshot(&self, path: &Path) -> std::io::Result<()> {
+        let format = ScreenshotFormat::from_path(path).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("no screenshot format for {}", path.display()),
+            )
+        })?;
+        std::fs::write(path, format.export(&self.frame))
+    }
+
+    /// Starts writing every rendered frame to an asciicast file at `path`.
+    pub fn start_recording(&mut self, path: &Path) -> std::io::Result<()> {
+        let mut recorder = CastRecorder::create(path, self.width, self.height)?;
+        recorder.setup()?;
+        self.recorder = Some(recorder);
+        // the cast has to start from a complete picture
+        self.invalidate();
+        Ok(())
+    }
+
+    pub fn stop_recording(&mut self) -> std::io::Result<()> {
+        match self.recorder.take() {
+            Some(mut recorder) => recorder.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Forget what is on screen so the next render clears and repaints every cell,
+    /// e.g. after a resize or when something else has scribbled on the terminal.
+    pub fn invalidate(&mut self) {
+        self.full_repaint = true;
+    }
+
     pub fn render(&mut self) -> std::io::Result<()> {
-        queue!(self.stdout, cursor::MoveTo(0, 0))?;
+        self.flatten();
 
-        for x in 0..self.height {
-            let line = String::from_iter(self.frame[x as usize].iter());
-            queue!(self.stdout, style::Print(line), cursor::MoveToNextLine(1))?;
+        let mut out = Tee {
+            backend: self.backend.as_mut(),
+            recorder: self.recorder.as_mut(),
+            recorder_error: None,
+        };
+
+        let full_repaint = std::mem::take(&mut self.full_repaint);
+        if full_repaint {
+            out.clear()?;
         }
 
-        if let Some(logger) = self.logger {
-            for msg in logger.drain() {
-                queue!(
-                    self.stdout,
-                    style::Print(msg),
-                    terminal::Clear(terminal::ClearType::UntilNewLine),
-                    cursor::MoveToNextLine(1),
-                )?;
+        // style of the last cell printed, so runs only restyle when it changes
+        let mut pen: Option<Cell> = None;
+
+        for y in 0..self.height {
+            let back = &self.frame[y as usize];
+            let front = &mut self.front[y as usize];
+
+            let mut x = 0;
+            while x < back.len() {
+                if !full_repaint && back[x] == front[x] {
+                    x += 1;
+                    continue;
+                }
+
+                // extend the run over any short stretches of unchanged cells
+                let start = x;
+                let mut end = x + 1;
+                x += 1;
+                while x < back.len() && x - end <= Self::MAX_RUN_GAP {
+                    if full_repaint || back[x] != front[x] {
+                        end = x + 1;
+                    }
+                    x += 1;
+                }
+
+                out.move_to(start as Dimension, y)?;
+
+                let mut run = String::new();
+                for cell in &back[start..end] {
+                    if !pen.is_some_and(|pen| pen.same_style(cell)) {
+                        if !run.is_empty() {
+                            out.print(&run)?;
+                            run.clear();
+                        }
+                        out.set_style(cell)?;
+                        pen = Some(*cell);
+                    }
+                    run.push(cell.glyph);
+                }
+                out.print(&run)?;
+
+                front[start..end].copy_from_slice(&back[start..end]);
+                x = end;
             }
         }
 
-        self.stdout.flush()?;
+        if pen.is_some() {
+            out.reset_style()?;
+        }
 
+        out.flush()?;
+        if let Some(err) = out.recorder_error {
+            log::warn!("stopped recording: {err}");
+            self.recorder = None;
+        }
         Ok(())
     }
 }
 
 impl Drop for Renderer {
     fn drop(&mut self) {
-        let _ = queue!(
-            self.stdout,
-            terminal::Clear(terminal::ClearType::All),
-            cursor::MoveTo(0, 0),
-            cursor::Show,
-        );
+        let _ = self.stop_recording();
+        // once the session restored the terminal, e.g. on panic, it's showing the
+        // user's own screen again
+        if TerminalSession::active() {
+            let _ = self.backend.restore();
+        }
+    }
+}
+
+/// Sends output to the backend and, while recording, to the recorder as well.
+/// The backend goes first, so a failing recorder can't keep a frame off screen.
+struct Tee<'a> {
+    backend: &'a mut dyn Backend,
+    recorder: Option<&'a mut CastRecorder>,
+    /// Why the recorder was dropped, if it failed.
+    recorder_error: Option<std::io::Error>,
+}
+
+impl Tee<'_> {
+    /// Runs `f` on the recorder, leaving it out of everything after its first error.
+    fn record(&mut self, f: impl FnOnce(&mut CastRecorder) -> std::io::Result<()>) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = f(recorder) {
+                self.recorder = None;
+                self.recorder_error = Some(err);
+            }
+        }
+    }
+}
+
+impl Backend for Tee<'_> {
+    fn setup(&mut self) -> std::io::Result<()> {
+        self.backend.setup()?;
+        self.record(|recorder| recorder.setup());
+        Ok(())
+    }
+
+    fn restore(&mut self) -> std::io::Result<()> {
+        self.backend.restore()?;
+        self.record(|recorder| recorder.restore());
+        Ok(())
+    }
+
+    fn clear(&mut self) -> std::io::Result<()> {
+        self.backend.clear()?;
+        self.record(|recorder| recorder.clear());
+        Ok(())
+    }
+
+    fn clear_line(&mut self) -> std::io::Result<()> {
+        self.backend.clear_line()?;
+        self.record(|recorder| recorder.clear_line());
+        Ok(())
+    }
+
+    fn move_to(&mut self, x: Dimension, y: Dimension) -> std::io::Result<()> {
+        self.backend.move_to(x, y)?;
+        self.record(|recorder| recorder.move_to(x, y));
+        Ok(())
+    }
+
+    fn set_style(&mut self, cell: &Cell) -> std::io::Result<()> {
+        self.backend.set_style(cell)?;
+        self.record(|recorder| recorder.set_style(cell));
+        Ok(())
+    }
+
+    fn reset_style(&mut self) -> std::io::Result<()> {
+        self.backend.reset_style()?;
+        self.record(|recorder| recorder.reset_style());
+        Ok(())
+    }
+
+    fn print(&mut self, text: &str) -> std::io::Result<()> {
+        self.backend.print(text)?;
+        self.record(|recorder| recorder.print(text));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.backend.flush()?;
+        self.record(|recorder| recorder.flush());
+        Ok(())
     }
 }
 
-pub type Sprite = Vec<Vec<char>>;
+pub type Sprite = Vec<Vec<Cell>>;
+
+/// Builds a sprite from rows of anything cell-like, e.g. plain `char` art.
+pub fn into_sprite<T: Into<Cell>>(rows: Vec<Vec<T>>) -> Sprite {
+    rows.into_iter()
+        .map(|row| row.into_iter().map(Into::into).collect())
+        .collect()
+}
+
+/// Turns every cell showing `key` transparent, e.g. the padding around sprite art.
+pub fn key_out(sprite: &mut Sprite, key: char) {
+    for cell in sprite.iter_mut().flatten() {
+        if cell.glyph == key {
+            *cell = Cell::TRANSPARENT;
+        }
+    }
+}
 
 pub trait Drawable {
     fn draw(&self, camera: &Camera, renderer: &mut Renderer);
+
+    /// The layer `Renderer::draw` puts this on.
+    fn layer(&self) -> Layer {
+        Layer::Entities
+    }
 }
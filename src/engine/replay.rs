@@ -0,0 +1,344 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use super::{Button, ButtonState, InputEvent, InputSource, Pos};
+
+const MAGIC: &[u8; 8] = b"SPLATRPL";
+const VERSION: u8 = 2;
+
+/// The buttons a replay drives, in the order they're numbered in replay files,
+/// so only ever append to this. Everything else, like quitting or the console,
+/// stays with the person watching.
+pub const REPLAY_BUTTONS: [Button; 11] = [
+    Button::Up,
+    Button::Down,
+    Button::Left,
+    Button::Right,
+    Button::Jump,
+    Button::Grapple,
+    Button::Reset,
+    Button::Space,
+    Button::LeftMouse,
+    Button::RightMouse,
+    Button::MiddleMouse,
+];
+
+/// A tag byte with this bit set stands for a run of up to 128 frames without
+/// changes, the low bits holding the length minus one.
+const IDLE_RUN: u8 = 0x80;
+const MAX_IDLE_RUN: u8 = 128;
+const MOUSE_MOVED: u8 = 0x01;
+
+/// What changed in the gameplay input during one update step.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayFrame {
+    pub buttons: Vec<(Button, ButtonState)>,
+    pub mouse_pos: Option<Pos>,
+}
+
+impl ReplayFrame {
+    fn is_empty(&self) -> bool {
+        self.buttons.is_empty() && self.mouse_pos.is_none()
+    }
+}
+
+/// Writes a replay file, one frame per update step. The file starts with the
+/// update interval and the stage, as replaying at any other rate or on any other
+/// stage would play out differently.
+pub struct InputRecorder {
+    file: BufWriter<File>,
+    idle: u8,
+    /// The gameplay input as a replay has it after the last frame written.
+    buttons: [ButtonState; REPLAY_BUTTONS.len()],
+    mouse_pos: Pos,
+    /// The last step's input, held back until the next step in case it's skipped.
+    pending: Option<([ButtonState; REPLAY_BUTTONS.len()], Pos)>,
+}
+
+impl InputRecorder {
+    /// `stage` tells apart what the run is played on, e.g. a hash of the stage.
+    pub fn create(path: &Path, step: Duration, stage: u64) -> std::io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&[VERSION])?;
+        file.write_all(&(step.as_nanos() as u64).to_le_bytes())?;
+        file.write_all(&stage.to_le_bytes())?;
+        Ok(Self {
+            file,
+            idle: 0,
+            buttons: [ButtonState::Released; REPLAY_BUTTONS.len()],
+            mouse_pos: Pos::ZERO,
+            pending: None,
+        })
+    }
+
+    /// Records the state of the `REPLAY_BUTTONS` and the mouse after a step.
+    pub fn record(
+        &mut self,
+        buttons: [ButtonState; REPLAY_BUTTONS.len()],
+        mouse_pos: Pos,
+    ) -> std::io::Result<()> {
+        self.write_pending()?;
+        self.pending = Some((buttons, mouse_pos));
+        Ok(())
+    }
+
+    /// Leaves the last recorded step out, e.g. one that was paused. Whatever
+    /// changed during it goes into the next step instead.
+    pub fn skip(&mut self) {
+        self.pending = None;
+    }
+
+    fn write_pending(&mut self) -> std::io::Result<()> {
+        let Some((buttons, mouse_pos)) = self.pending.take() else {
+            return Ok(());
+        };
+
+        settle(&mut self.buttons);
+        let frame = ReplayFrame {
+            buttons: REPLAY_BUTTONS
+                .into_iter()
+                .zip(buttons.into_iter().zip(self.buttons))
+                .filter(|(_, (state, before))| state != before)
+                .map(|(button, (state, _))| (button, state))
+                .collect(),
+            mouse_pos: (mouse_pos != self.mouse_pos).then_some(mouse_pos),
+        };
+        self.buttons = buttons;
+        self.mouse_pos = mouse_pos;
+        self.write_frame(&frame)
+    }
+
+    fn write_frame(&mut self, frame: &ReplayFrame) -> std::io::Result<()> {
+        if frame.is_empty() {
+            self.idle += 1;
+            if self.idle == MAX_IDLE_RUN {
+                self.flush_idle()?;
+            }
+            return Ok(());
+        }
+
+        self.flush_idle()?;
+        let mut tag = (frame.buttons.len() as u8) << 1;
+        if frame.mouse_pos.is_some() {
+            tag |= MOUSE_MOVED;
+        }
+        self.file.write_all(&[tag])?;
+
+        for &(button, state) in &frame.buttons {
+            self.file.write_all(&[encode_button(button, state)])?;
+        }
+        if let Some(pos) = frame.mouse_pos {
+            self.file.write_all(&pos.x.to_le_bytes())?;
+            self.file.write_all(&pos.y.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn flush_idle(&mut self) -> std::io::Result<()> {
+        if self.idle > 0 {
+            self.file.write_all(&[IDLE_RUN | (self.idle - 1)])?;
+            self.idle = 0;
+        }
+        Ok(())
+    }
+
+    pub fn finish(&mut self) -> std::io::Result<()> {
+        self.write_pending()?;
+        self.flush_idle()?;
+        self.file.flush()
+    }
+}
+
+impl Drop for InputRecorder {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+/// Reads back a file written by `InputRecorder`, frame by frame. As an
+/// `InputSource` it keeps setting the replayed buttons and mouse position,
+/// so nothing else gets a say over them.
+pub struct InputReplay {
+    data: Vec<u8>,
+    cursor: usize,
+    idle: u8,
+    frames: u64,
+    buttons: [ButtonState; REPLAY_BUTTONS.len()],
+    mouse_pos: Pos,
+    finished: bool,
+}
+
+impl InputReplay {
+    /// Fails if the file was recorded with a different update interval than `step`,
+    /// or on a different `stage` than given to `InputRecorder::create`.
+    pub fn open(path: &Path, step: Duration, stage: u64) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        let step_start = MAGIC.len() + 1;
+        let header_len = step_start + 8 + 8;
+        if data.len() < header_len || &data[..MAGIC.len()] != MAGIC {
+            return Err(invalid(format!("{} isn't a replay", path.display())));
+        }
+        if data[MAGIC.len()] != VERSION {
+            return Err(invalid(format!(
+                "unsupported replay version {}",
+                data[MAGIC.len()]
+            )));
+        }
+
+        let nanos = u64::from_le_bytes(data[step_start..step_start + 8].try_into().unwrap());
+        if Duration::from_nanos(nanos) != step {
+            return Err(invalid(format!(
+                "replay was recorded with a {:?} update interval, not {step:?}",
+                Duration::from_nanos(nanos)
+            )));
+        }
+        let recorded_stage =
+            u64::from_le_bytes(data[step_start + 8..header_len].try_into().unwrap());
+        if recorded_stage != stage {
+            return Err(invalid(
+                "replay was recorded on a different stage".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            data,
+            cursor: header_len,
+            idle: 0,
+            frames: 0,
+            buttons: [ButtonState::Released; REPLAY_BUTTONS.len()],
+            mouse_pos: Pos::ZERO,
+            finished: false,
+        })
+    }
+
+    /// How many frames have been played back so far.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// The next frame, or `None` once the replay is over.
+    pub fn next_frame(&mut self) -> std::io::Result<Option<ReplayFrame>> {
+        if self.idle > 0 {
+            self.idle -= 1;
+            self.frames += 1;
+            return Ok(Some(ReplayFrame::default()));
+        }
+
+        let Some(tag) = self.read_bytes::<1>()? else {
+            return Ok(None);
+        };
+        let tag = tag[0];
+        self.frames += 1;
+
+        if tag & IDLE_RUN != 0 {
+            self.idle = tag & !IDLE_RUN;
+            return Ok(Some(ReplayFrame::default()));
+        }
+
+        let mut frame = ReplayFrame::default();
+        for _ in 0..tag >> 1 {
+            let [byte] = self.read_bytes::<1>()?.ok_or_else(truncated)?;
+            frame.buttons.push(decode_button(byte)?);
+        }
+        if tag & MOUSE_MOVED != 0 {
+            let x = self.read_bytes::<4>()?.ok_or_else(truncated)?;
+            let y = self.read_bytes::<4>()?.ok_or_else(truncated)?;
+            frame.mouse_pos = Some(Pos::new(f32::from_le_bytes(x), f32::from_le_bytes(y)));
+        }
+        Ok(Some(frame))
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> std::io::Result<Option<[u8; N]>> {
+        if self.cursor == self.data.len() {
+            return Ok(None);
+        }
+        let bytes = self
+            .data
+            .get(self.cursor..self.cursor + N)
+            .ok_or_else(truncated)?;
+        self.cursor += N;
+        Ok(Some(bytes.try_into().unwrap()))
+    }
+}
+
+impl InputSource for InputReplay {
+    fn poll(&mut self, events: &mut Vec<InputEvent>) -> std::io::Result<()> {
+        let Some(frame) = self.next_frame()? else {
+            self.finished = true;
+            return Ok(());
+        };
+
+        settle(&mut self.buttons);
+        for (button, state) in frame.buttons {
+            self.buttons[button_index(button)] = state;
+        }
+        if let Some(pos) = frame.mouse_pos {
+            self.mouse_pos = pos;
+        }
+
+        for (button, state) in REPLAY_BUTTONS.into_iter().zip(self.buttons) {
+            events.push(InputEvent::Set(button, state));
+        }
+        events.push(InputEvent::MouseAt(self.mouse_pos));
+        Ok(())
+    }
+
+    fn finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Turns this frame's presses and releases into holds, as a new step starts.
+fn settle(buttons: &mut [ButtonState]) {
+    for state in buttons {
+        match state {
+            ButtonState::PressedThisFrame => *state = ButtonState::Pressed,
+            ButtonState::ReleasedThisFrame => *state = ButtonState::Released,
+            _ => (),
+        }
+    }
+}
+
+fn button_index(button: Button) -> usize {
+    REPLAY_BUTTONS
+        .iter()
+        .position(|replayed| *replayed == button)
+        .expect("only replayed buttons go into replays")
+}
+
+fn encode_button(button: Button, state: ButtonState) -> u8 {
+    let index = button_index(button);
+    let state = match state {
+        ButtonState::PressedThisFrame => 0,
+        ButtonState::Pressed => 1,
+        ButtonState::ReleasedThisFrame => 2,
+        ButtonState::Released => 3,
+    };
+    (index as u8) << 2 | state
+}
+
+fn decode_button(byte: u8) -> std::io::Result<(Button, ButtonState)> {
+    let button = *REPLAY_BUTTONS
+        .get((byte >> 2) as usize)
+        .ok_or_else(|| invalid(format!("unknown button {} in replay", byte >> 2)))?;
+    let state = match byte & 0b11 {
+        0 => ButtonState::PressedThisFrame,
+        1 => ButtonState::Pressed,
+        2 => ButtonState::ReleasedThisFrame,
+        _ => ButtonState::Released,
+    };
+    Ok((button, state))
+}
+
+fn invalid(msg: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+fn truncated() -> std::io::Error {
+    invalid("replay ends in the middle of a frame".to_string())
+}
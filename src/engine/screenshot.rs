@@ -0,0 +1,197 @@
+use std::path::Path;
+
+use super::{cells_to_text, Backend, Cell, Color, TerminalBackend};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotFormat {
+    /// Just the glyphs.
+    Text,
+    /// Glyphs with the escape codes to style them, for `cat` or an ANSI art viewer.
+    Ansi,
+    /// A self-contained page with the frame in a styled `<pre>` grid.
+    Html,
+}
+
+impl ScreenshotFormat {
+    pub const ALL: [Self; 3] = [Self::Text, Self::Ansi, Self::Html];
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "txt" => Some(Self::Text),
+            "ans" => Some(Self::Ansi),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Ansi => "ans",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn export(&self, cells: &[Vec<Cell>]) -> String {
+        match self {
+            Self::Text => cells_to_text(cells),
+            Self::Ansi => to_ansi(cells),
+            Self::Html => to_html(cells),
+        }
+    }
+}
+
+fn to_ansi(cells: &[Vec<Cell>]) -> String {
+    let mut terminal = TerminalBackend::new(Vec::new());
+    // writing to a vec can't fail
+    for row in cells {
+        let mut pen: Option<Cell> = None;
+        for cell in row {
+            if !pen.is_some_and(|pen| pen.same_style(cell)) {
+                let _ = terminal.set_style(cell);
+                pen = Some(*cell);
+            }
+            let _ = terminal.print(&cell.glyph.to_string());
+        }
+        let _ = terminal.reset_style();
+        let _ = terminal.print("\n");
+    }
+    String::from_utf8_lossy(&terminal.into_inner()).into_owned()
+}
+
+fn to_html(cells: &[Vec<Cell>]) -> String {
+    let mut html = String::from(concat!(
+        "<!DOCTYPE html>\n",
+        "<html>\n<head>\n<meta charset=\"utf-8\">\n<title>splat</title>\n",
+        "<style>\n",
+        "body { background: #000; color: #ccc; margin: 0; }\n",
+        "pre { font-family: monospace; line-height: 1.2; margin: 1em; }\n",
+        "</style>\n</head>\n<body>\n<pre>",
+    ));
+
+    for row in cells {
+        let mut run = String::new();
+        let mut pen: Option<Cell> = None;
+        for cell in row {
+            if !pen.is_some_and(|pen| pen.same_style(cell)) {
+                if let Some(pen) = pen {
+                    push_span(&mut html, &pen, &run);
+                }
+                run.clear();
+                pen = Some(*cell);
+            }
+            match cell.glyph {
+                '<' => run.push_str("&lt;"),
+                '>' => run.push_str("&gt;"),
+                '&' => run.push_str("&amp;"),
+                glyph => run.push(glyph),
+            }
+        }
+        if let Some(pen) = pen {
+            push_span(&mut html, &pen, &run);
+        }
+        html.push('\n');
+    }
+
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+fn push_span(html: &mut String, pen: &Cell, text: &str) {
+    let (mut fg, mut bg) = (css_color(pen.fg), css_color(pen.bg));
+    if pen.reverse {
+        (fg, bg) = (bg.or(Some("#000".into())), fg.or(Some("#ccc".into())));
+    }
+
+    let mut style = String::new();
+    if let Some(fg) = fg {
+        style.push_str(&format!("color: {fg}; "));
+    }
+    if let Some(bg) = bg {
+        style.push_str(&format!("background: {bg}; "));
+    }
+    if pen.bold {
+        style.push_str("font-weight: bold; ");
+    }
+    if pen.dim {
+        style.push_str("opacity: 0.6; ");
+    }
+
+    if style.is_empty() {
+        html.push_str(text);
+    } else {
+        html.push_str(&format!(
+            "<span style=\"{}\">{text}</span>",
+            style.trim_end()
+        ));
+    }
+}
+
+/// The CSS for a terminal color, or `None` for the terminal default.
+fn css_color(color: Color) -> Option<String> {
+    let (r, g, b) = match color {
+        Color::Reset => return None,
+        Color::Black => (0x00, 0x00, 0x00),
+        Color::DarkRed => (0xcd, 0x00, 0x00),
+        Color::DarkGreen => (0x00, 0xcd, 0x00),
+        Color::DarkYellow => (0xcd, 0xcd, 0x00),
+        Color::DarkBlue => (0x00, 0x00, 0xee),
+        Color::DarkMagenta => (0xcd, 0x00, 0xcd),
+        Color::DarkCyan => (0x00, 0xcd, 0xcd),
+        Color::Grey => (0xe5, 0xe5, 0xe5),
+        Color::DarkGrey => (0x7f, 0x7f, 0x7f),
+        Color::Red => (0xff, 0x00, 0x00),
+        Color::Green => (0x00, 0xff, 0x00),
+        Color::Yellow => (0xff, 0xff, 0x00),
+        Color::Blue => (0x5c, 0x5c, 0xff),
+        Color::Magenta => (0xff, 0x00, 0xff),
+        Color::Cyan => (0x00, 0xff, 0xff),
+        Color::White => (0xff, 0xff, 0xff),
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::AnsiValue(value) => return css_color(ansi_to_rgb(value)),
+    };
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+/// Expands an xterm 256 color palette index.
+fn ansi_to_rgb(value: u8) -> Color {
+    const BASIC: [Color; 16] = [
+        Color::Black,
+        Color::DarkRed,
+        Color::DarkGreen,
+        Color::DarkYellow,
+        Color::DarkBlue,
+        Color::DarkMagenta,
+        Color::DarkCyan,
+        Color::Grey,
+        Color::DarkGrey,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+
+    match value {
+        0..=15 => BASIC[value as usize],
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let value = value - 16;
+            Color::Rgb {
+                r: level(value / 36),
+                g: level(value / 6 % 6),
+                b: level(value % 6),
+            }
+        }
+        232..=255 => {
+            let grey = 8 + (value - 232) * 10;
+            Color::Rgb {
+                r: grey,
+                g: grey,
+                b: grey,
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use super::{Button, InputEvent, InputSource, Pos};
+
+/// Input laid out ahead of time, by update step counting from 0 for the first
+/// `Input::update`. Lets the game be driven without a terminal, see `Input::scripted`.
+#[derive(Debug, Clone)]
+pub struct ScriptedInput {
+    timeline: BTreeMap<u64, Vec<InputEvent>>,
+    step: u64,
+}
+
+impl ScriptedInput {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            timeline: BTreeMap::new(),
+            step: 0,
+        }
+    }
+
+    /// Sends `event` at `step`, after any sent there before.
+    pub fn at(mut self, step: u64, event: InputEvent) -> Self {
+        self.timeline.entry(step).or_default().push(event);
+        self
+    }
+
+    pub fn press(self, step: u64, button: Button) -> Self {
+        self.at(step, InputEvent::Press(button))
+    }
+
+    pub fn release(self, step: u64, button: Button) -> Self {
+        self.at(step, InputEvent::Release(button))
+    }
+
+    /// Presses `button` at `step` and lets go of it `steps` later.
+    pub fn hold(self, step: u64, steps: u64, button: Button) -> Self {
+        self.press(step, button).release(step + steps, button)
+    }
+
+    /// Presses `button` for a single step.
+    pub fn tap(self, step: u64, button: Button) -> Self {
+        self.hold(step, 1, button)
+    }
+
+    /// Puts the mouse over the world position `pos` from `step` on.
+    pub fn mouse_at(self, step: u64, pos: Pos) -> Self {
+        self.at(step, InputEvent::MouseAt(pos))
+    }
+
+    /// The step the next `poll` sends events for.
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn poll(&mut self, events: &mut Vec<InputEvent>) -> std::io::Result<()> {
+        if let Some(step_events) = self.timeline.remove(&self.step) {
+            events.extend(step_events);
+        }
+        self.step += 1;
+        Ok(())
+    }
+
+    fn finished(&self) -> bool {
+        self.timeline.is_empty()
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    backtrace::Backtrace,
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crossterm::{
+    cursor,
+    event::{
+        DisableMouseCapture, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
+    },
+    execute, style, terminal,
+};
+
+/// Whether the terminal currently needs restoring, so it only happens once
+/// no matter whether a panic, a signal or a normal exit gets there first.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether keyboard enhancement flags were pushed and still need popping.
+static KEYBOARD_FLAGS: AtomicBool = AtomicBool::new(false);
+
+/// Owns the terminal for the lifetime of the game. Switches to the alternate screen
+/// so the user's scrollback survives, and puts everything back on drop, on panic,
+/// and after SIGINT/SIGTERM. Create it before anything else touches the terminal.
+pub struct TerminalSession {
+    terminated: Arc<AtomicBool>,
+}
+
+impl TerminalSession {
+    pub fn start() -> std::io::Result<Self> {
+        execute!(std::io::stdout(), terminal::EnterAlternateScreen)?;
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        std::panic::set_hook(Box::new(|info| {
+            Self::restore();
+            log::error!("{info}");
+            eprintln!("{info}");
+            eprintln!("{}", Backtrace::force_capture());
+        }));
+
+        // signal handlers can't safely touch the terminal, so just flag the
+        // game loop to wind down and let the drops restore things
+        let terminated = Arc::new(AtomicBool::new(false));
+        for signal in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+            signal_hook::flag::register(signal, Arc::clone(&terminated))?;
+        }
+
+        Ok(Self { terminated })
+    }
+
+    /// Whether the process was asked to stop by a signal.
+    pub fn terminated(&self) -> bool {
+        self.terminated.load(Ordering::Relaxed)
+    }
+
+    /// Whether the terminal is set up, i.e. the session started and nothing has
+    /// restored it yet. Once it isn't, drops shouldn't write to the terminal anymore,
+    /// as it's back to showing the user's own screen.
+    pub fn active() -> bool {
+        ACTIVE.load(Ordering::SeqCst)
+    }
+
+    /// Turns on keyboard enhancements, which `restore` turns off again if
+    /// `pop_keyboard_flags` doesn't get to it first.
+    pub fn push_keyboard_flags(flags: KeyboardEnhancementFlags) -> std::io::Result<()> {
+        execute!(std::io::stdout(), PushKeyboardEnhancementFlags(flags))?;
+        KEYBOARD_FLAGS.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Undoes `push_keyboard_flags`, if nothing has yet. Popping more than was
+    /// pushed would take off flags the shell set for itself.
+    pub fn pop_keyboard_flags() -> std::io::Result<()> {
+        if KEYBOARD_FLAGS.swap(false, Ordering::SeqCst) {
+            execute!(std::io::stdout(), PopKeyboardEnhancementFlags)?;
+        }
+        Ok(())
+    }
+
+    /// Undoes everything the engine does to the terminal. Safe to call more than once.
+    pub fn restore() {
+        if !ACTIVE.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        let mut stdout = std::io::stdout();
+        let _ = terminal::disable_raw_mode();
+        // flags are kept per screen, so they go before leaving the alternate one
+        let _ = Self::pop_keyboard_flags();
+        let _ = execute!(
+            stdout,
+            DisableMouseCapture,
+            style::SetAttribute(style::Attribute::Reset),
+            style::ResetColor,
+            cursor::Show,
+            terminal::LeaveAlternateScreen,
+        );
+        let _ = stdout.flush();
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        Self::restore();
+    }
+}
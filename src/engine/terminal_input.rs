@@ -0,0 +1,226 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    event::{
+        poll, read, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind,
+        KeyboardEnhancementFlags, MouseButton, MouseEvent, MouseEventKind,
+    },
+    execute, terminal,
+};
+
+use super::{
+    Bindings, Button, InputEvent, InputSource, ScreenPos, TerminalSession, Trigger, Wheel,
+};
+
+impl Button {
+    fn from_mouse_event(event: &MouseEvent) -> Option<Self> {
+        match event.kind {
+            MouseEventKind::Up(mouse_button) | MouseEventKind::Down(mouse_button) => {
+                match mouse_button {
+                    MouseButton::Left => Some(Self::LeftMouse),
+                    MouseButton::Right => Some(Self::RightMouse),
+                    MouseButton::Middle => Some(Self::MiddleMouse),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A key held down in a terminal that won't say when it's released.
+struct HeldKey {
+    last_seen: Instant,
+    repeating: bool,
+}
+
+/// Live input from the terminal, which it puts into raw mode with mouse
+/// capture for as long as it's around.
+///
+/// Keyboard keys only report releases in terminals that accept the kitty keyboard
+/// protocol. Elsewhere this guesses when a key was let go from when its
+/// auto-repeat stops, so held keys behave about the same either way.
+pub struct TerminalInput {
+    bindings: Bindings,
+    /// Also whether keyboard enhancement flags were pushed to get them.
+    key_releases: bool,
+    held_keys: HashMap<Button, HeldKey>,
+    /// Buttons pressed by the wheel, to let go of at the next poll.
+    wheel_presses: Vec<Button>,
+}
+
+impl TerminalInput {
+    /// How long after a press without a repeat a key counts as released. Has to
+    /// outlast the usual auto-repeat delay, or held keys would flicker.
+    const FIRST_REPEAT_TIMEOUT: Duration = Duration::from_millis(600);
+
+    /// Same, once the key is repeating, which happens every 30-50ms or so.
+    const REPEAT_TIMEOUT: Duration = Duration::from_millis(120);
+
+    pub fn new(bindings: Bindings) -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+
+        execute!(std::io::stdout(), EnableMouseCapture)?;
+
+        let key_releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if key_releases {
+            TerminalSession::push_keyboard_flags(KeyboardEnhancementFlags::all())?;
+        } else {
+            log::info!("terminal doesn't report key releases, guessing them from key repeat");
+        }
+
+        Ok(Self {
+            bindings,
+            key_releases,
+            held_keys: HashMap::new(),
+            wheel_presses: Vec::new(),
+        })
+    }
+
+    /// Whether the terminal reports key releases, rather than them being guessed.
+    pub fn key_releases(&self) -> bool {
+        self.key_releases
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    pub fn set_bindings(&mut self, bindings: Bindings) {
+        self.bindings = bindings;
+    }
+
+    /// Without release events, auto-repeat shows up as more presses.
+    /// Only the first one is a real press, the rest just keep the key held.
+    fn press_unreleasable_key(&mut self, button: Button, events: &mut Vec<InputEvent>) {
+        let now = Instant::now();
+        match self.held_keys.get_mut(&button) {
+            Some(held) => {
+                held.last_seen = now;
+                held.repeating = true;
+            }
+            None => {
+                events.push(InputEvent::Press(button));
+                self.held_keys.insert(
+                    button,
+                    HeldKey {
+                        last_seen: now,
+                        repeating: false,
+                    },
+                );
+            }
+        }
+    }
+
+    fn mouse_button(&self, mouse_event: &MouseEvent, events: &mut Vec<InputEvent>) {
+        let (mouse_button, pressed) = match mouse_event.kind {
+            MouseEventKind::Down(button) => (button, true),
+            MouseEventKind::Up(button) => (button, false),
+            _ => return,
+        };
+
+        let trigger = Trigger::Mouse(mouse_button);
+        let buttons = self.bindings.buttons(trigger, mouse_event.modifiers);
+        for button in buttons
+            .into_iter()
+            .chain(Button::from_mouse_event(mouse_event))
+        {
+            events.push(if pressed {
+                InputEvent::Press(button)
+            } else {
+                InputEvent::Release(button)
+            });
+        }
+    }
+
+    /// A wheel notch presses the buttons bound to it until the next poll.
+    fn scroll_wheel(&mut self, mouse_event: &MouseEvent, events: &mut Vec<InputEvent>) {
+        let (wheel, notches) = match mouse_event.kind {
+            MouseEventKind::ScrollUp => (Wheel::Up, ScreenPos::new(0, -1)),
+            MouseEventKind::ScrollDown => (Wheel::Down, ScreenPos::new(0, 1)),
+            MouseEventKind::ScrollLeft => (Wheel::Left, ScreenPos::new(-1, 0)),
+            MouseEventKind::ScrollRight => (Wheel::Right, ScreenPos::new(1, 0)),
+            _ => return,
+        };
+
+        events.push(InputEvent::Scroll(notches));
+        let trigger = Trigger::Wheel(wheel);
+        for button in self.bindings.buttons(trigger, mouse_event.modifiers) {
+            events.push(InputEvent::Press(button));
+            self.wheel_presses.push(button);
+        }
+    }
+
+    /// Releases held keys whose auto-repeat has gone quiet.
+    fn release_quiet_keys(&mut self, events: &mut Vec<InputEvent>) {
+        let now = Instant::now();
+        self.held_keys.retain(|button, held| {
+            let timeout = if held.repeating {
+                Self::REPEAT_TIMEOUT
+            } else {
+                Self::FIRST_REPEAT_TIMEOUT
+            };
+            if now - held.last_seen < timeout {
+                return true;
+            }
+
+            events.push(InputEvent::Release(*button));
+            false
+        });
+    }
+}
+
+impl InputSource for TerminalInput {
+    fn poll(&mut self, events: &mut Vec<InputEvent>) -> std::io::Result<()> {
+        events.extend(self.wheel_presses.drain(..).map(InputEvent::Release));
+
+        while poll(Duration::ZERO)? {
+            match read()? {
+                Event::Key(key_event) => {
+                    let trigger = Trigger::Key(key_event.code);
+                    for button in self.bindings.buttons(trigger, key_event.modifiers) {
+                        match key_event.kind {
+                            KeyEventKind::Press if self.key_releases => {
+                                events.push(InputEvent::Press(button));
+                            }
+                            KeyEventKind::Press => self.press_unreleasable_key(button, events),
+                            KeyEventKind::Release => events.push(InputEvent::Release(button)),
+                            KeyEventKind::Repeat => (),
+                        }
+                    }
+                }
+                Event::Mouse(mouse_event) => {
+                    events.push(InputEvent::MouseMoved(ScreenPos::new(
+                        mouse_event.column.into(),
+                        mouse_event.row.into(),
+                    )));
+                    self.mouse_button(&mouse_event, events);
+                    self.scroll_wheel(&mouse_event, events);
+                }
+                Event::Resize(columns, rows) => events.push(InputEvent::Resized(columns, rows)),
+                _ => (),
+            }
+        }
+
+        self.release_quiet_keys(events);
+        Ok(())
+    }
+}
+
+impl Drop for TerminalInput {
+    fn drop(&mut self) {
+        // after the session restored the terminal, e.g. on panic, anything sent
+        // would land on the user's own screen
+        if !TerminalSession::active() {
+            return;
+        }
+
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(std::io::stdout(), DisableMouseCapture);
+        if self.key_releases {
+            let _ = TerminalSession::pop_keyboard_flags();
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use super::{Cell, Dimension, Layer, Rect, Renderer};
+
+/// Which part of the text lines up with the x coordinate it's printed at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    /// The text starts at `x`.
+    Left,
+    /// The text is centered on `x`.
+    Center,
+    /// The text ends just before `x`.
+    Right,
+}
+
+/// A run of text sharing one style. Only the style of the cell is used, not its glyph.
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub text: String,
+    pub style: Cell,
+}
+
+impl Span {
+    pub fn new(text: impl Into<String>, style: Cell) -> Self {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+}
+
+impl From<&str> for Span {
+    fn from(value: &str) -> Self {
+        Self::new(value, Cell::BLANK)
+    }
+}
+
+impl From<String> for Span {
+    fn from(value: String) -> Self {
+        Self::new(value, Cell::BLANK)
+    }
+}
+
+impl Renderer {
+    /// Prints a single line of unstyled text, clipped to the frame.
+    pub fn print(&mut self, x: Dimension, y: Dimension, align: Align, text: &str) {
+        self.print_styled(x, y, align, &[Span::from(text)]);
+    }
+
+    /// Prints a single line made of styled spans, clipped to the frame.
+    pub fn print_styled(&mut self, x: Dimension, y: Dimension, align: Align, spans: &[Span]) {
+        let frame = Rect::new(0, 0, self.width(), self.height());
+        self.print_cells(x, y, align, &Self::span_cells(spans), frame);
+    }
+
+    /// Word wraps spans into `rect`, breaking words that don't fit on a line by
+    /// themselves. Anything past the bottom of `rect` is dropped.
+    /// Returns how many lines were printed.
+    pub fn print_wrapped(&mut self, rect: Rect, align: Align, spans: &[Span]) -> Dimension {
+        let x = match align {
+            Align::Left => rect.x,
+            Align::Center => rect.x + rect.width / 2,
+            Align::Right => rect.right(),
+        };
+
+        let lines = Self::wrap(&Self::span_cells(spans), rect.width as usize);
+        let mut printed = 0;
+        for (line, y) in lines.iter().zip(rect.y..rect.bottom()) {
+            self.print_cells(x, y, align, line, rect);
+            printed += 1;
+        }
+        printed
+    }
+
+    /// Prints `msg` wrapped and centered in the middle of the frame, on the HUD layer.
+    pub fn paint_notice(&mut self, msg: &str) {
+        let spans = [Span::from(msg)];
+        let lines = Self::wrap(&Self::span_cells(&spans), self.width() as usize).len();
+        let top = self.height().saturating_sub(lines as Dimension) / 2;
+        let rect = Rect::new(0, top, self.width(), self.height() - top);
+
+        self.with_layer(Layer::Hud, |renderer| {
+            renderer.print_wrapped(rect, Align::Center, &spans);
+        });
+    }
+
+    fn print_cells(
+        &mut self,
+        x: Dimension,
+        y: Dimension,
+        align: Align,
+        cells: &[Cell],
+        clip: Rect,
+    ) {
+        if y < clip.y || y >= clip.bottom() {
+            return;
+        }
+
+        let len = cells.len() as i64;
+        let start = match align {
+            Align::Left => x as i64,
+            Align::Center => x as i64 - len / 2,
+            Align::Right => x as i64 - len,
+        };
+
+        for (i, cell) in cells.iter().enumerate() {
+            let x = start + i as i64;
+            if x >= clip.x as i64 && x < clip.right() as i64 {
+                self.paint(x as Dimension, y, *cell);
+            }
+        }
+    }
+
+    fn span_cells(spans: &[Span]) -> Vec<Cell> {
+        spans
+            .iter()
+            .flat_map(|span| {
+                span.text.chars().map(|glyph| Cell {
+                    glyph,
+                    ..span.style
+                })
+            })
+            .collect()
+    }
+
+    fn wrap(cells: &[Cell], width: usize) -> Vec<Vec<Cell>> {
+        let mut lines = Vec::new();
+        if width == 0 {
+            return lines;
+        }
+
+        for paragraph in cells.split(|cell| cell.glyph == '\n') {
+            let mut line: Vec<Cell> = Vec::new();
+
+            for mut word in paragraph
+                .split(|cell| cell.glyph == ' ')
+                .filter(|word| !word.is_empty())
+            {
+                let gap = if line.is_empty() { 0 } else { 1 };
+                if line.len() + gap + word.len() > width && !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+
+                while word.len() > width {
+                    lines.push(word[..width].to_vec());
+                    word = &word[width..];
+                }
+
+                if !line.is_empty() {
+                    line.push(Cell {
+                        glyph: ' ',
+                        ..word[0]
+                    });
+                }
+                line.extend_from_slice(word);
+            }
+
+            lines.push(line);
+        }
+
+        lines
+    }
+}
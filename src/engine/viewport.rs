@@ -0,0 +1,248 @@
+use super::{
+    BorderStyle, Camera, CameraEffects, Dimension, Drawable, Layer, Panel, Pos, Rect, Renderer,
+    ScreenPos,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Where a viewport goes when `Viewports::layout` carves up the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Shares whatever the strips leave with the other `Fill` viewports.
+    Fill,
+    /// This many full width rows along the bottom, e.g. for a HUD.
+    Bottom(Dimension),
+    /// A fixed size box in a corner of the `Fill` area, drawn over it.
+    Inset {
+        corner: Corner,
+        width: Dimension,
+        height: Dimension,
+    },
+}
+
+/// How `Fill` viewports share their area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Split {
+    /// Side by side.
+    Horizontal,
+    /// Stacked on top of each other.
+    Vertical,
+}
+
+/// A camera together with the part of the terminal it draws into.
+pub struct Viewport {
+    pub camera: Camera,
+    pub visible: bool,
+    placement: Placement,
+    border: Option<BorderStyle>,
+    title: Option<String>,
+    transparent: bool,
+    rect: Rect,
+}
+
+impl Viewport {
+    pub fn new(placement: Placement) -> Self {
+        Self {
+            camera: Camera {
+                pos: Pos::ZERO,
+                frame_pos: ScreenPos::ZERO,
+                width: 0,
+                height: 0,
+                effects: CameraEffects::new(),
+            },
+            visible: true,
+            placement,
+            border: None,
+            title: None,
+            transparent: false,
+            rect: Rect::new(0, 0, 0, 0),
+        }
+    }
+
+    pub fn border(mut self, border: BorderStyle) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Shown in the top border, if there is one.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Lets whatever is under an inset show through instead of blanking it first.
+    pub fn transparent(mut self) -> Self {
+        self.transparent = true;
+        self
+    }
+
+    /// The whole area of the viewport, border included.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// The area the camera draws into.
+    pub fn inner(&self) -> Rect {
+        if self.border.is_some() {
+            self.rect.inset(1)
+        } else {
+            self.rect
+        }
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+        let inner = self.inner();
+        self.camera.frame_pos = ScreenPos::new(inner.x.into(), inner.y.into());
+        self.camera.width = inner.width;
+        self.camera.height = inner.height;
+    }
+}
+
+/// Handle to a viewport added to `Viewports`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewportId(usize);
+
+/// Lays out several cameras in the terminal, e.g. for split screen,
+/// picture-in-picture or a separate HUD.
+pub struct Viewports {
+    split: Split,
+    viewports: Vec<Viewport>,
+}
+
+impl Viewports {
+    pub fn new(split: Split) -> Self {
+        Self {
+            split,
+            viewports: Vec::new(),
+        }
+    }
+
+    /// Takes effect at the next `layout`.
+    pub fn add(&mut self, viewport: Viewport) -> ViewportId {
+        self.viewports.push(viewport);
+        ViewportId(self.viewports.len() - 1)
+    }
+
+    pub fn get(&self, id: ViewportId) -> &Viewport {
+        &self.viewports[id.0]
+    }
+
+    pub fn get_mut(&mut self, id: ViewportId) -> &mut Viewport {
+        &mut self.viewports[id.0]
+    }
+
+    pub fn camera(&self, id: ViewportId) -> &Camera {
+        &self.get(id).camera
+    }
+
+    pub fn camera_mut(&mut self, id: ViewportId) -> &mut Camera {
+        &mut self.get_mut(id).camera
+    }
+
+    /// Fits every viewport into a terminal of the given size. Hidden viewports
+    /// keep their place, so toggling one doesn't shuffle the others around.
+    pub fn layout(&mut self, width: Dimension, height: Dimension) {
+        let mut area = Rect::new(0, 0, width, height);
+
+        for viewport in &mut self.viewports {
+            if let Placement::Bottom(rows) = viewport.placement {
+                let rows = rows.min(area.height);
+                area.height -= rows;
+                viewport.set_rect(Rect::new(area.x, area.bottom(), area.width, rows));
+            }
+        }
+
+        let fills = self
+            .viewports
+            .iter()
+            .filter(|viewport| viewport.placement == Placement::Fill)
+            .count() as Dimension;
+        let mut fill_index = 0;
+
+        for viewport in &mut self.viewports {
+            match viewport.placement {
+                Placement::Fill => {
+                    viewport.set_rect(Self::split_rect(area, self.split, fill_index, fills));
+                    fill_index += 1;
+                }
+                Placement::Inset {
+                    corner,
+                    width,
+                    height,
+                } => {
+                    let (width, height) = (width.min(area.width), height.min(area.height));
+                    let x = match corner {
+                        Corner::TopLeft | Corner::BottomLeft => area.x,
+                        Corner::TopRight | Corner::BottomRight => area.right() - width,
+                    };
+                    let y = match corner {
+                        Corner::TopLeft | Corner::TopRight => area.y,
+                        Corner::BottomLeft | Corner::BottomRight => area.bottom() - height,
+                    };
+                    viewport.set_rect(Rect::new(x, y, width, height));
+                }
+                Placement::Bottom(_) => (),
+            }
+        }
+    }
+
+    /// The `index`th of `count` even slices of `area`, the last one taking the remainder.
+    fn split_rect(area: Rect, split: Split, index: Dimension, count: Dimension) -> Rect {
+        match split {
+            Split::Horizontal => {
+                let width = area.width / count;
+                let x = area.x + width * index;
+                let width = if index + 1 == count {
+                    area.right() - x
+                } else {
+                    width
+                };
+                Rect::new(x, area.y, width, area.height)
+            }
+            Split::Vertical => {
+                let height = area.height / count;
+                let y = area.y + height * index;
+                let height = if index + 1 == count {
+                    area.bottom() - y
+                } else {
+                    height
+                };
+                Rect::new(area.x, y, area.width, height)
+            }
+        }
+    }
+
+    /// Draws `drawables` through the viewport's camera, clipped to it, along with
+    /// its border. Viewports drawn later go on top.
+    pub fn draw(&self, id: ViewportId, drawables: &[&dyn Drawable], renderer: &mut Renderer) {
+        let viewport = self.get(id);
+        if !viewport.visible {
+            return;
+        }
+
+        if matches!(viewport.placement, Placement::Inset { .. }) && !viewport.transparent {
+            renderer.erase(viewport.rect);
+        }
+
+        if let Some(border) = viewport.border {
+            let mut panel = Panel::new(viewport.rect, border);
+            if let Some(title) = &viewport.title {
+                panel = panel.title(title.as_str());
+            }
+            renderer.with_layer(Layer::Hud, |renderer| panel.paint(renderer));
+        }
+
+        renderer.with_clip(viewport.inner(), |renderer| {
+            for drawable in drawables {
+                renderer.draw(*drawable, &viewport.camera);
+            }
+        });
+    }
+}
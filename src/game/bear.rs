@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::engine::{Animation, Drawable, Pos};
+use crate::engine::{Animation, Clock, Drawable, Pos};
 
 pub struct Bear {
     pos: Pos,
@@ -1174,12 +1174,12 @@ vec![],
 vec![],
 ],
 ]
-                , Duration::from_millis(100), false),
+                , Duration::from_millis(100), false).key_out(' '),
         }
     }
 
-    pub fn update(&mut self) {
-        self.anim.update();
+    pub fn update(&mut self, clock: &Clock) {
+        self.anim.update(clock);
     }
 }
 
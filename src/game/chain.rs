@@ -1,8 +1,9 @@
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use crate::engine::{Drawable, Pos, Ray};
+use crate::engine::{BrailleCanvas, Clock, Color, Drawable, Pos, Ray};
 
 const LINK_TIME: Duration = Duration::from_millis(5);
+const LINK_COLOR: Color = Color::DarkYellow;
 
 enum State {
     Deploying,
@@ -16,7 +17,7 @@ pub struct Chain {
     state: State,
     links: Vec<Pos>,
     num_links_out: usize,
-    start_time: Instant,
+    start_time: Duration,
     just_deployed: bool,
 }
 
@@ -27,19 +28,19 @@ impl Chain {
             state: State::Retracted,
             links: Vec::new(),
             num_links_out: 0,
-            start_time: Instant::now(),
+            start_time: Duration::ZERO,
             just_deployed: false,
         }
     }
 
-    pub fn update(&mut self) {
+    pub fn update(&mut self, clock: &Clock) {
         self.just_deployed = false;
         self.links = self.ray.march();
 
         match self.state {
             State::Deploying => {
                 let num_links_out =
-                    self.start_time.elapsed().as_secs_f64() / LINK_TIME.as_secs_f64();
+                    clock.since(self.start_time).as_secs_f64() / LINK_TIME.as_secs_f64();
                 let num_links_out = (num_links_out as usize).min(self.links.len());
                 self.num_links_out = num_links_out;
 
@@ -50,7 +51,7 @@ impl Chain {
             State::Retracting => {
                 if self.num_links_out == 0 {
                     self.state = State::Retracted;
-                } else if self.start_time.elapsed() >= LINK_TIME {
+                } else if clock.since(self.start_time) >= LINK_TIME {
                     self.num_links_out -= 1;
                 }
             }
@@ -58,9 +59,9 @@ impl Chain {
         }
     }
 
-    pub fn deploy(&mut self) {
+    pub fn deploy(&mut self, clock: &Clock) {
         self.state = State::Deploying;
-        self.start_time = Instant::now();
+        self.start_time = clock.now();
         self.just_deployed = true;
     }
 
@@ -72,9 +73,9 @@ impl Chain {
         self.just_deployed
     }
 
-    pub fn retract(&mut self) {
+    pub fn retract(&mut self, clock: &Clock) {
         self.state = State::Retracting;
-        self.start_time = Instant::now();
+        self.start_time = clock.now();
     }
 
     pub fn tangent(&self) -> Pos {
@@ -85,8 +86,19 @@ impl Chain {
 
 impl Drawable for Chain {
     fn draw(&self, camera: &crate::engine::Camera, renderer: &mut crate::engine::Renderer) {
-        for link in self.links.iter().take(self.num_links_out) {
-            camera.paint_dot('•', *link, renderer);
-        }
+        let links_out = &self.links[..self.num_links_out.min(self.links.len())];
+        let (Some(first), Some(last)) = (links_out.first(), links_out.last()) else {
+            return;
+        };
+
+        let mut canvas = BrailleCanvas::new();
+        canvas.plot_ray(
+            Ray {
+                start: *first,
+                end: *last,
+            },
+            LINK_COLOR,
+        );
+        canvas.draw(camera, renderer);
     }
 }
@@ -1,5 +1,14 @@
 use std::time::Duration;
 
+use crate::engine::Dimension;
+
 pub const UPDATE_RATE: f32 = 200.0; // Hz
 pub const DELTA_TIME: f32 = 1.0 / UPDATE_RATE;
 pub const UPDATE_INTERVAL: Duration = Duration::from_nanos((DELTA_TIME * 1e9) as u64);
+
+/// Frames drawn per second, independent of the simulation rate.
+pub const RENDER_RATE: f32 = 60.0; // Hz
+
+/// Smallest terminal the game will draw into, below this a notice is shown instead.
+pub const MIN_COLUMNS: Dimension = 40;
+pub const MIN_ROWS: Dimension = 24;
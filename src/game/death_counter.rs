@@ -0,0 +1,32 @@
+use crate::engine::{Align, Dimension, Drawable, Layer};
+
+use super::Player;
+
+pub struct DeathCounter {
+    count: u32,
+}
+
+impl DeathCounter {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    pub fn update(&mut self, player: &Player) {
+        self.count = player.death_count();
+    }
+}
+
+impl Drawable for DeathCounter {
+    fn draw(&self, camera: &crate::engine::Camera, renderer: &mut crate::engine::Renderer) {
+        if self.count > 1 {
+            let death_counter = format!("you died {} times", self.count);
+            let (x, y) = (camera.frame_pos.x, camera.frame_pos.y);
+            renderer.print(x as Dimension, y as Dimension, Align::Left, &death_counter);
+        }
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Hud
+    }
+}
@@ -1,12 +1,164 @@
-use crate::engine::{Camera, ScreenPos};
+use crate::engine::{Camera, Clock, Coord, Input, Pos, ScreenCoord, ScreenPos};
 
 use super::Player;
 
-impl Camera {
-    pub fn update(&mut self, player: &Player) {
+/// What the camera leads the player towards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LookAhead {
+    None,
+    /// Where the player will be this many seconds from now at their current velocity.
+    Velocity(Coord),
+    /// This far towards the mouse.
+    Aim(Coord),
+}
+
+pub struct FollowConfig {
+    /// Half the size of the box around the focus the player can move in
+    /// without dragging the camera along.
+    pub dead_zone: Pos,
+    /// Roughly how long the camera takes to catch up, in seconds.
+    pub smooth_time: Coord,
+    pub look_ahead: LookAhead,
+    /// Cap on how far the look-ahead can lead the player by.
+    pub max_look_ahead: Coord,
+    /// Jumps further than this (respawns, teleports) cut instead of panning.
+    pub snap_distance: Coord,
+    /// Cells the player is always kept away from the edge of the view,
+    /// however far behind the smoothing is.
+    pub margin: ScreenCoord,
+    /// Area the view is kept inside of, as the size in cells of a region starting
+    /// at the origin, e.g. `Stage::extent`. `None` lets the camera roam freely.
+    pub bounds: Option<ScreenPos>,
+}
+
+impl Default for FollowConfig {
+    fn default() -> Self {
+        Self {
+            dead_zone: Pos::new(4.0, 6.0),
+            smooth_time: 0.2,
+            look_ahead: LookAhead::Velocity(0.25),
+            max_look_ahead: 16.0,
+            snap_distance: 120.0,
+            margin: 2,
+            bounds: None,
+        }
+    }
+}
+
+/// Eases the camera after the player instead of locking onto them.
+pub struct FollowCam {
+    pub config: FollowConfig,
+    goal: Option<Pos>,
+    focus: Pos,
+    prev_focus: Pos,
+    vel: Pos,
+}
+
+impl FollowCam {
+    pub fn new(config: FollowConfig) -> Self {
+        Self {
+            config,
+            goal: None,
+            focus: Pos::ZERO,
+            prev_focus: Pos::ZERO,
+            vel: Pos::ZERO,
+        }
+    }
+
+    pub fn update(&mut self, player: &Player, input: &Input, clock: &Clock) {
+        let target = player.pos + self.look_ahead(player, input);
+        self.prev_focus = self.focus;
+
+        let Some(goal) = &mut self.goal else {
+            self.goal = Some(target);
+            self.snap(target);
+            return;
+        };
+
+        // only drag the goal along once the target leaves the dead zone
+        let dead_zone = self.config.dead_zone;
+        goal.x = goal.x.clamp(target.x - dead_zone.x, target.x + dead_zone.x);
+        goal.y = goal.y.clamp(target.y - dead_zone.y, target.y + dead_zone.y);
+        let goal = *goal;
+
+        if (goal - self.focus).magnitude() > self.config.snap_distance {
+            self.snap(goal);
+            return;
+        }
+
+        self.smooth_damp(goal, clock.delta_secs());
+    }
+
+    /// Keeps the focus in place through a step that isn't simulated, see `Player::hold`.
+    pub fn hold(&mut self) {
+        self.prev_focus = self.focus;
+    }
+
+    /// Points `camera` at the focus as of `alpha` of the way through the current step.
+    pub fn apply(&self, camera: &mut Camera, player: &Player, alpha: f32) {
+        let half_size = ScreenPos::new((camera.width / 2).into(), (camera.height / 2).into());
+        let focus = ScreenPos::from(self.prev_focus.lerp(self.focus, alpha));
+
         // quantize to screen coords to avoid collision alignment looking weird
-        let offset = ScreenPos::new((self.width / 2).into(), (self.height / 2).into());
-        let quantized = ScreenPos::from(player.pos) - offset;
-        self.pos = quantized.into();
+        let mut cam_pos = focus - half_size;
+
+        // but never lose the player off screen
+        let player_pos = ScreenPos::from(player.pos_at(alpha));
+        let margin = self.config.margin;
+        let max = ScreenPos::new(camera.width.into(), camera.height.into())
+            - ScreenPos::new(margin + 1, margin + 1);
+        cam_pos.x = cam_pos
+            .x
+            .min(player_pos.x - margin)
+            .max(player_pos.x - max.x);
+        cam_pos.y = cam_pos
+            .y
+            .min(player_pos.y - margin)
+            .max(player_pos.y - max.y);
+
+        camera.pos = cam_pos.into();
+        if let Some(extent) = self.config.bounds {
+            camera.clamp_to(ScreenPos::ZERO, extent);
+        }
+    }
+
+    fn look_ahead(&self, player: &Player, input: &Input) -> Pos {
+        let lead = match self.config.look_ahead {
+            LookAhead::None => Pos::ZERO,
+            LookAhead::Velocity(secs) => player.vel().scale(secs),
+            LookAhead::Aim(dist) => {
+                let aim = input.mouse_pos - player.pos;
+                if aim.magnitude() > 0.0 {
+                    aim.normalize().scale(dist)
+                } else {
+                    Pos::ZERO
+                }
+            }
+        };
+
+        let len = lead.magnitude();
+        if len > self.config.max_look_ahead {
+            lead.scale(self.config.max_look_ahead / len)
+        } else {
+            lead
+        }
+    }
+
+    fn snap(&mut self, pos: Pos) {
+        self.focus = pos;
+        self.prev_focus = pos;
+        self.vel = Pos::ZERO;
+    }
+
+    /// Critically damped spring towards `goal`, so it eases in without overshooting.
+    fn smooth_damp(&mut self, goal: Pos, dt: Coord) {
+        let omega = 2.0 / self.config.smooth_time.max(Coord::EPSILON);
+        let x = omega * dt;
+        let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+
+        let offset = self.focus - goal;
+        let temp = (self.vel + offset.scale(omega)).scale(dt);
+        self.vel = (self.vel - temp.scale(omega)).scale(decay);
+        self.focus = goal + (offset + temp).scale(decay);
     }
 }
@@ -0,0 +1,236 @@
+use crate::engine::{
+    BorderStyle, BrailleCanvas, Button, Camera, Cell, Color, Coord, Dimension, Drawable, Input,
+    Layer, Panel, Pos, Rect, Renderer, ScreenCoord, ScreenPos,
+};
+
+use super::{Player, Stage, Tile};
+
+const TERRAIN_COLOR: Color = Color::Grey;
+const VIEW_STYLE: Cell = Cell::new(' ').fg(Color::Yellow);
+const PLAYER_MARKER: Cell = Cell::new('@').fg(Color::Red).bold();
+const CHECKPOINT_MARKER: Cell = Cell::new('*').fg(Color::Cyan).bold();
+
+/// A sub-dot is lit when at least this much of the stage under it is solid.
+/// Kept low so walls a single cell thick survive heavy downsampling.
+const DENSITY_THRESHOLD: f32 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapMode {
+    Hidden,
+    Corner,
+    Full,
+}
+
+impl MapMode {
+    fn next(self) -> Self {
+        match self {
+            Self::Hidden => Self::Corner,
+            Self::Corner => Self::Full,
+            Self::Full => Self::Hidden,
+        }
+    }
+}
+
+/// How stage cells map onto braille sub-dots for a given map size, keeping the
+/// stage's aspect ratio and centering it.
+struct Scale {
+    /// Stage columns per sub-dot. Each sub-dot covers half as many rows,
+    /// since a cell is twice as tall as it is wide.
+    cols_per_dot: Coord,
+    offset: Pos,
+}
+
+impl Scale {
+    fn new(extent: ScreenPos, width: Dimension, height: Dimension) -> Self {
+        let (dots_x, dots_y) = (width as Coord * 2.0, height as Coord * 4.0);
+        let (cols, rows) = (extent.x as Coord, extent.y as Coord);
+        let cols_per_dot = (cols / dots_x).max(rows * 2.0 / dots_y).max(Coord::EPSILON);
+
+        let used = Pos::new(cols / cols_per_dot, rows * 2.0 / cols_per_dot);
+        Self {
+            cols_per_dot,
+            offset: (Pos::new(dots_x, dots_y) - used).scale(0.5),
+        }
+    }
+
+    /// Sub-dot under the stage cell position `pos`, which may be fractional.
+    fn dot_at(&self, pos: Pos) -> Pos {
+        self.offset + Pos::new(pos.x, pos.y * 2.0).scale(1.0 / self.cols_per_dot)
+    }
+
+    /// Top left corner of the stage area under sub-dot `dot`.
+    fn stage_at(&self, dot: Pos) -> Pos {
+        let pos = (dot - self.offset).scale(self.cols_per_dot);
+        Pos::new(pos.x, pos.y / 2.0)
+    }
+}
+
+/// A shrunk down overview of the whole stage, drawn with braille to show how
+/// dense the terrain is, along with the player, checkpoints and the game view.
+pub struct Minimap {
+    mode: MapMode,
+    extent: ScreenPos,
+    /// Solid tiles above and left of each cell, a summed-area table one row
+    /// and column bigger than the stage so any rectangle is four lookups.
+    solid_sums: Vec<Vec<u32>>,
+    player: Pos,
+    checkpoints: Vec<Pos>,
+    view_pos: ScreenPos,
+    view_size: ScreenPos,
+}
+
+impl Minimap {
+    pub fn new(stage: &Stage) -> Self {
+        let extent = stage.extent();
+        let (width, height) = extent.indices();
+
+        let mut solid_sums = vec![vec![0; width + 1]; height + 1];
+        for y in 0..height {
+            for x in 0..width {
+                let solid = !matches!(
+                    stage.tile(x, y),
+                    Tile::Nothing | Tile::OutOfBounds | Tile::Checkpoint
+                );
+                solid_sums[y + 1][x + 1] =
+                    solid as u32 + solid_sums[y][x + 1] + solid_sums[y + 1][x] - solid_sums[y][x];
+            }
+        }
+
+        Self {
+            mode: MapMode::Hidden,
+            extent,
+            solid_sums,
+            player: Pos::ZERO,
+            checkpoints: Vec::new(),
+            view_pos: ScreenPos::ZERO,
+            view_size: ScreenPos::ZERO,
+        }
+    }
+
+    pub fn mode(&self) -> MapMode {
+        self.mode
+    }
+
+    /// Cycles hidden, corner and full screen on `Button::Map`, and keeps track
+    /// of what to mark on the map.
+    pub fn update(&mut self, input: &Input, player: &Player, camera: &Camera) {
+        if input.pressed_this_frame(Button::Map) {
+            self.mode = self.mode.next();
+        }
+
+        self.player = player.pos;
+        if self.checkpoints.len() != player.checkpoints_reached().len() {
+            self.checkpoints = player.checkpoints_reached().to_vec();
+        }
+        self.view_pos = ScreenPos::from(camera.pos);
+        self.view_size = ScreenPos::new(camera.width.into(), camera.height.into());
+    }
+
+    /// Fraction of the stage cells in `[x0, x1) x [y0, y1)` that are solid.
+    fn density(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> f32 {
+        let (width, height) = self.extent.indices();
+        let (x0, x1) = (x0.min(width), x1.min(width));
+        let (y0, y1) = (y0.min(height), y1.min(height));
+        let area = (x1 - x0) * (y1 - y0);
+        if area == 0 {
+            return 0.0;
+        }
+
+        let sums = &self.solid_sums;
+        let solid = sums[y1][x1] + sums[y0][x0] - sums[y0][x1] - sums[y1][x0];
+        solid as f32 / area as f32
+    }
+
+    fn dot_lit(&self, scale: &Scale, dot_x: ScreenCoord, dot_y: ScreenCoord) -> bool {
+        let start = scale.stage_at(Pos::new(dot_x as Coord, dot_y as Coord));
+        let end = scale.stage_at(Pos::new(dot_x as Coord + 1.0, dot_y as Coord + 1.0));
+        if end.x <= 0.0 || end.y <= 0.0 {
+            return false;
+        }
+
+        // always cover at least one cell, or zoomed in maps come out patchy
+        let (x0, y0) = (start.x.max(0.0) as usize, start.y.max(0.0) as usize);
+        let x1 = (end.x.ceil() as usize).max(x0 + 1);
+        let y1 = (end.y.ceil() as usize).max(y0 + 1);
+        self.density(x0, y0, x1, y1) >= DENSITY_THRESHOLD
+    }
+
+    /// Cell of the map, relative to its top left, showing the world position `pos`.
+    fn map_cell(scale: &Scale, pos: ScreenPos) -> ScreenPos {
+        let dot = scale.dot_at(Pos::new(pos.x as Coord + 0.5, pos.y as Coord + 0.5));
+        ScreenPos::new(
+            (dot.x / 2.0).floor() as ScreenCoord,
+            (dot.y / 4.0).floor() as ScreenCoord,
+        )
+    }
+}
+
+impl Drawable for Minimap {
+    fn draw(&self, camera: &Camera, renderer: &mut Renderer) {
+        let scale = Scale::new(self.extent, camera.width, camera.height);
+        let paint = |renderer: &mut Renderer, cell: ScreenPos, dot: Cell| {
+            let pos = camera.frame_pos + cell;
+            if cell.x >= 0
+                && cell.y >= 0
+                && cell.x < camera.width as ScreenCoord
+                && cell.y < camera.height as ScreenCoord
+            {
+                renderer.paint(pos.x as Dimension, pos.y as Dimension, dot);
+            }
+        };
+
+        for y in 0..camera.height as ScreenCoord {
+            for x in 0..camera.width as ScreenCoord {
+                let mut bits = 0;
+                for sub_y in 0..4 {
+                    for sub_x in 0..2 {
+                        if self.dot_lit(&scale, x * 2 + sub_x, y * 4 + sub_y) {
+                            bits |= BrailleCanvas::bit(sub_x, sub_y);
+                        }
+                    }
+                }
+
+                if bits != 0 {
+                    let glyph = Cell::new(BrailleCanvas::glyph(bits)).fg(TERRAIN_COLOR);
+                    paint(renderer, ScreenPos::new(x, y), glyph);
+                }
+            }
+        }
+
+        // outline the game view, cut down to the part that fits on the map
+        let last = ScreenPos::new(camera.width.into(), camera.height.into()) - ScreenPos::new(1, 1);
+        let fit = |cell: ScreenPos| {
+            ScreenPos::new(
+                cell.x.clamp(0, last.x.max(0)),
+                cell.y.clamp(0, last.y.max(0)),
+            )
+        };
+        let view_start = fit(Self::map_cell(&scale, self.view_pos));
+        let view_end = fit(Self::map_cell(&scale, self.view_pos + self.view_size));
+        let view_size = view_end - view_start + ScreenPos::new(1, 1);
+        if view_size.x >= 2 && view_size.y >= 2 {
+            let frame_start = camera.frame_pos + view_start;
+            let rect = Rect::new(
+                frame_start.x as Dimension,
+                frame_start.y as Dimension,
+                view_size.x as Dimension,
+                view_size.y as Dimension,
+            );
+            Panel::new(rect, BorderStyle::Single)
+                .style(VIEW_STYLE)
+                .paint(renderer);
+        }
+
+        for checkpoint in &self.checkpoints {
+            let cell = Self::map_cell(&scale, ScreenPos::from(*checkpoint));
+            paint(renderer, cell, CHECKPOINT_MARKER);
+        }
+
+        let cell = Self::map_cell(&scale, ScreenPos::from(self.player));
+        paint(renderer, cell, PLAYER_MARKER);
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Hud
+    }
+}
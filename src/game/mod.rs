@@ -1,12 +1,12 @@
 mod constants;
 pub use constants::*;
 
-mod border;
-pub use border::*;
-
 mod stage;
 pub use stage::*;
 
+mod parallax;
+pub use parallax::*;
+
 mod player;
 pub use player::*;
 
@@ -14,9 +14,16 @@ mod chain;
 pub use chain::*;
 
 mod follow_cam;
+pub use follow_cam::*;
 
 mod bear;
 pub use bear::*;
 
 mod timer;
 pub use timer::*;
+
+mod death_counter;
+pub use death_counter::*;
+
+mod minimap;
+pub use minimap::*;
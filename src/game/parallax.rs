@@ -0,0 +1,79 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind},
+    path::Path,
+};
+
+use crate::engine::{Camera, Cell, Color, Coord, Drawable, Layer, Pos, Renderer, Sprite};
+
+const DEFAULT_PARALLAX: Pos = Pos::new(0.5, 0.5);
+const BACKGROUND_COLOR: Color = Color::DarkGrey;
+
+/// Scenery behind the stage that scrolls slower than it, for a sense of depth.
+/// Never collides with anything.
+pub struct ParallaxLayer {
+    /// How far the layer moves per unit the camera moves. 0 is pinned to the
+    /// screen like a far away sky, 1 moves with the stage.
+    pub factor: Pos,
+    sprite: Sprite,
+}
+
+impl ParallaxLayer {
+    /// Reads a layer drawn like a stage file. It can start with a line like
+    /// `parallax 0.2 0.1` for the horizontal and vertical factors, or just
+    /// `parallax 0.2` for both, otherwise it gets `DEFAULT_PARALLAX`.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let mut factor = DEFAULT_PARALLAX;
+        let mut sprite = Vec::new();
+
+        let file = File::open(path)?;
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if i == 0 {
+                if let Some(spec) = line.strip_prefix("parallax ") {
+                    factor = Self::parse_factor(spec).ok_or_else(|| {
+                        std::io::Error::new(
+                            ErrorKind::InvalidData,
+                            format!("bad parallax header in {}: {line}", path.display()),
+                        )
+                    })?;
+                    continue;
+                }
+            }
+
+            sprite.push(
+                line.chars()
+                    .map(|dot| match dot {
+                        ' ' => Cell::TRANSPARENT,
+                        _ => Cell::new(dot).fg(BACKGROUND_COLOR),
+                    })
+                    .collect(),
+            );
+        }
+
+        Ok(Self { factor, sprite })
+    }
+
+    fn parse_factor(spec: &str) -> Option<Pos> {
+        let mut parts = spec.split_whitespace().map(str::parse::<Coord>);
+        let x = parts.next()?.ok()?;
+        let y = match parts.next() {
+            Some(y) => y.ok()?,
+            None => x,
+        };
+        parts.next().is_none().then_some(Pos::new(x, y))
+    }
+}
+
+impl Drawable for ParallaxLayer {
+    fn draw(&self, camera: &Camera, renderer: &mut Renderer) {
+        // anchor the layer so it lines up with the stage when the camera is at
+        // the origin, and lags behind by `1 - factor` from there
+        let pos = camera.pos * (Pos::ONE - self.factor);
+        camera.paint_sprite(&self.sprite, pos, renderer);
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Background
+    }
+}
@@ -1,7 +1,8 @@
 use std::time::Duration;
 
 use crate::engine::{
-    Animation, Button, Coord, Drawable, InifiniteRay, Input, Pos, Ray, ScreenPos, Signed,
+    Animation, Button, CameraEffects, Cell, Clock, Color, Coord, Drawable, InifiniteRay, Input,
+    Pos, Ray, ScreenPos, Signed,
 };
 
 use super::{Chain, Stage, Tile, DELTA_TIME};
@@ -13,9 +14,15 @@ pub const SPRING_KICK: Coord = 1.2;
 pub const MIN_SPRING_VEL: Coord = 50.0;
 pub const JUMP_VEL: Coord = 60.0;
 pub const MAX_ROPE_LEN: usize = 100;
+pub const HARD_LANDING_VEL: Coord = 80.0;
+
+const PLAYER_DOT: Cell = Cell::new('O').fg(Color::Yellow).bold();
+const DEATH_COLOR: Color = Color::Red;
+const DEATH_HIT_STOP: Duration = Duration::from_millis(120);
 
 pub struct Player {
     pub pos: Pos,
+    prev_pos: Pos,
     vel: Pos,
     chain: Chain,
     stuck: bool,
@@ -23,6 +30,7 @@ pub struct Player {
     death_anim_offset: Pos,
     death_count: u32,
     checkpoint: Pos,
+    checkpoints_reached: Vec<Pos>,
 }
 
 impl Player {
@@ -49,11 +57,14 @@ impl Player {
             ],
             Duration::from_millis(100),
             true,
-        );
+        )
+        .tint(DEATH_COLOR)
+        .key_out(' ');
         death_anim.pause();
 
         Self {
             pos: starting_pos,
+            prev_pos: starting_pos,
             vel: Pos::ZERO,
             chain: Chain::new(Ray {
                 start: Pos::ZERO,
@@ -64,32 +75,75 @@ impl Player {
             death_anim_offset: ScreenPos::new(-1, -1).into(),
             death_count: 0,
             checkpoint: starting_pos,
+            checkpoints_reached: Vec::new(),
         }
     }
 
-    pub fn update(&mut self, input: &Input, stage: &Stage) {
+    pub fn update(
+        &mut self,
+        input: &Input,
+        stage: &Stage,
+        clock: &Clock,
+        effects: &mut CameraEffects,
+    ) {
+        self.prev_pos = self.pos;
         self.teleport(input);
-        self.death(input);
+        self.death(input, clock);
         self.jump(input);
-        self.chain_throw(input, stage);
+        self.chain_throw(input, stage, clock);
         let new_pos = self.kinematics();
-        self.collision(new_pos, stage);
+        self.collision(new_pos, stage, effects);
         self.chain.ray.start = self.pos;
     }
 
+    /// Keeps the player in place through a step that isn't simulated, e.g. during
+    /// a hit-stop, so drawing stops interpolating from the step before.
+    pub fn hold(&mut self) {
+        self.prev_pos = self.pos;
+    }
+
+    pub fn vel(&self) -> Pos {
+        self.vel
+    }
+
+    pub fn death_count(&self) -> u32 {
+        self.death_count
+    }
+
+    pub fn checkpoint(&self) -> Pos {
+        self.checkpoint
+    }
+
+    /// Every checkpoint touched so far, in the order they were reached.
+    pub fn checkpoints_reached(&self) -> &[Pos] {
+        &self.checkpoints_reached
+    }
+
+    /// Whether the death animation is playing, i.e. a respawn is coming up.
+    pub fn dying(&self) -> bool {
+        self.death_anim.playing()
+    }
+
+    /// Where the player is `alpha` of the way from the previous step to the current one.
+    pub fn pos_at(&self, alpha: f32) -> Pos {
+        self.prev_pos.lerp(self.pos, alpha)
+    }
+
     fn teleport(&mut self, input: &Input) {
         if cfg!(debug_assertions) && input.pressed_this_frame(Button::Space) {
             self.pos = input.mouse_pos;
+            self.prev_pos = self.pos;
             self.vel = Pos::ZERO;
             self.stuck = true;
         }
     }
 
-    fn death(&mut self, input: &Input) {
-        self.death_anim.update();
+    fn death(&mut self, input: &Input, clock: &Clock) {
+        self.death_anim.update(clock);
         if self.death_anim.done() || input.pressed_this_frame(Button::Reset) {
             self.death_count += 1;
             self.pos = self.checkpoint;
+            self.prev_pos = self.pos;
             self.vel = Pos::ZERO;
             self.chain = Chain::new(Ray {
                 start: Pos::ZERO,
@@ -102,15 +156,15 @@ impl Player {
     }
 
     fn jump(&mut self, input: &Input) {
-        if self.stuck && input.pressed_this_frame(Button::RightMouse) {
+        if self.stuck && input.pressed_this_frame(Button::Jump) {
             self.stuck = false;
             let jump_dir = (input.mouse_pos - self.pos).normalize();
             self.vel = jump_dir.scale(JUMP_VEL);
         }
     }
 
-    fn chain_throw(&mut self, input: &Input, stage: &Stage) {
-        if input.pressed_this_frame(Button::LeftMouse) && self.pos != input.mouse_pos {
+    fn chain_throw(&mut self, input: &Input, stage: &Stage, clock: &Clock) {
+        if input.pressed_this_frame(Button::Grapple) && self.pos != input.mouse_pos {
             let ray = InifiniteRay::new(self.pos, input.mouse_pos - self.pos);
             for pos in ray.take(MAX_ROPE_LEN) {
                 if !matches!(
@@ -119,14 +173,14 @@ impl Player {
                 ) {
                     self.stuck = false;
                     self.chain.ray.end = pos;
-                    self.chain.deploy();
+                    self.chain.deploy(clock);
                     break;
                 }
             }
-        } else if input.released_this_frame(Button::LeftMouse) {
-            self.chain.retract();
+        } else if input.released_this_frame(Button::Grapple) {
+            self.chain.retract(clock);
         } else {
-            self.chain.update();
+            self.chain.update(clock);
         }
     }
 
@@ -190,7 +244,7 @@ impl Player {
         self.pos + self.vel.scale(DELTA_TIME)
     }
 
-    fn collision(&mut self, new_pos: Pos, stage: &Stage) {
+    fn collision(&mut self, new_pos: Pos, stage: &Stage, effects: &mut CameraEffects) {
         let traj = Ray {
             start: self.pos,
             end: new_pos,
@@ -203,18 +257,33 @@ impl Player {
                     continue;
                 }
                 Tile::Something => {
+                    let speed = self.vel.magnitude();
+                    if !self.stuck && speed > HARD_LANDING_VEL {
+                        effects.shake(speed / HARD_LANDING_VEL * 0.2);
+                        effects.kick(self.vel.scale(2.0 / speed));
+                    }
                     self.vel = Pos::ZERO;
                     self.stuck = true;
                 }
                 Tile::Spring => {
+                    effects.shake(0.2);
+                    effects.kick(Pos::new(0.0, 2.0));
                     self.vel.y *= -SPRING_KICK;
                     self.vel.y = self.vel.y.min(-MIN_SPRING_VEL);
                 }
                 Tile::Spike => {
+                    if !self.death_anim.playing() {
+                        effects.shake(0.8);
+                        effects.hit_stop(DEATH_HIT_STOP);
+                        effects.zoom_out(0.5);
+                    }
                     self.death_anim.play();
                 }
                 Tile::Checkpoint => {
                     self.checkpoint = ScreenPos::from(step).into();
+                    if !self.checkpoints_reached.contains(&self.checkpoint) {
+                        self.checkpoints_reached.push(self.checkpoint);
+                    }
                     self.pos = step;
                     continue;
                 }
@@ -227,22 +296,140 @@ impl Drawable for Player {
     fn draw(&self, camera: &crate::engine::Camera, renderer: &mut crate::engine::Renderer) {
         self.chain.draw(camera, renderer);
 
+        let pos = self.pos_at(renderer.alpha());
         if self.death_anim.playing() {
             camera.paint_sprite(
                 self.death_anim.get_frame(),
-                self.pos + self.death_anim_offset,
+                pos + self.death_anim_offset,
                 renderer,
             );
         } else {
-            camera.paint_dot('O', self.pos, renderer);
+            camera.paint_dot(PLAYER_DOT, pos, renderer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        engine::{Camera, ScriptedInput},
+        game::UPDATE_INTERVAL,
+    };
+
+    #[test]
+    fn lands_after_scripted_jump() {
+        let stage = Stage::parse(concat!(
+            "                    \n",
+            "                    \n",
+            "                    \n",
+            "                    \n",
+            "                    \n",
+            "####################\n",
+        ));
+        // standing in the row just above the floor
+        let start = Pos::new(4.0, 8.0);
+        let mut player = Player::new(start);
+
+        let script = ScriptedInput::new()
+            .mouse_at(0, Pos::new(8.0, -40.0))
+            .tap(0, Button::Jump);
+        let mut input = Input::scripted(script);
+        let camera = Camera {
+            pos: Pos::ZERO,
+            frame_pos: ScreenPos::ZERO,
+            width: 20,
+            height: 6,
+            effects: CameraEffects::new(),
+        };
+        let mut effects = CameraEffects::new();
+        let clock = Clock::new(UPDATE_INTERVAL);
+
+        for _ in 0..(3.0 / DELTA_TIME) as usize {
+            input.update(&camera).unwrap();
+            player.update(&input, &stage, &clock, &mut effects);
         }
 
-        if self.death_count > 1 {
-            let death_counter = format!("you died {} times", self.death_count);
-            let y = renderer.height() - 1;
-            for (i, dot) in death_counter.chars().enumerate() {
-                renderer.paint(i as u16, y, dot);
+        assert!(player.stuck);
+        assert_eq!(player.vel(), Pos::ZERO);
+        assert!(player.pos.x > start.x);
+        assert_eq!(ScreenPos::from(player.pos).y, ScreenPos::from(start).y);
+        assert!(matches!(
+            stage.check_pos(player.pos + Pos::new(0.0, 2.0)),
+            Tile::Something
+        ));
+    }
+
+    #[test]
+    fn replay_plays_back_recorded_run() {
+        let stage = Stage::parse(concat!(
+            "                              \n",
+            "                              \n",
+            "                              \n",
+            "                              \n",
+            "                              \n",
+            "##############################\n",
+        ));
+        let path = std::env::temp_dir().join(format!("splat-{}.rpl", std::process::id()));
+        let camera = Camera {
+            pos: Pos::ZERO,
+            frame_pos: ScreenPos::ZERO,
+            width: 30,
+            height: 6,
+            effects: CameraEffects::new(),
+        };
+        let clock = Clock::new(UPDATE_INTERVAL);
+        let steps = 800;
+
+        // steps in `paused` go by without the player, like with a small terminal
+        let run = |input: &mut Input, paused: std::ops::Range<usize>| {
+            let mut player = Player::new(Pos::new(4.0, 8.0));
+            let mut effects = CameraEffects::new();
+            let mut positions = Vec::new();
+            for step in 0.. {
+                if positions.len() == steps {
+                    break;
+                }
+                input.update(&camera).unwrap();
+                if paused.contains(&step) {
+                    input.skip_step();
+                    continue;
+                }
+                player.update(input, &stage, &clock, &mut effects);
+                positions.push(player.pos);
             }
+            positions
+        };
+
+        // the stretch between the jumps is longer than an idle run in the file,
+        // and the mouse moves during the pause
+        let script = ScriptedInput::new()
+            .mouse_at(0, Pos::new(8.0, -40.0))
+            .tap(0, Button::Jump)
+            .mouse_at(240, Pos::new(12.0, -40.0))
+            .tap(400, Button::Jump)
+            .hold(600, 20, Button::Left);
+        let mut input = Input::scripted(script);
+        input
+            .record_to(&path, UPDATE_INTERVAL, stage.checksum())
+            .unwrap();
+        let recorded = run(&mut input, 200..260);
+        drop(input);
+
+        let mut input = Input::scripted(ScriptedInput::new());
+        input
+            .replay_from(&path, UPDATE_INTERVAL, stage.checksum())
+            .unwrap();
+        let replayed = run(&mut input, 0..0);
+
+        let other_stage = Stage::parse("#");
+        let mismatch = input.replay_from(&path, UPDATE_INTERVAL, other_stage.checksum());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_ne!(recorded[0], recorded[steps - 1]);
+        for (step, (recorded, replayed)) in recorded.iter().zip(&replayed).enumerate() {
+            assert_eq!(recorded, replayed, "replay went off at step {step}");
         }
+        assert!(mismatch.is_err());
     }
 }
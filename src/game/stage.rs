@@ -1,10 +1,8 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
-};
+use std::path::Path;
 
-use crate::engine::{Drawable, Pos, ScreenPos};
+use super::ParallaxLayer;
+
+use crate::engine::{Cell, Color, Drawable, Layer, Pos, ScreenCoord, ScreenPos, Sprite};
 
 pub enum Tile {
     OutOfBounds,
@@ -27,22 +25,89 @@ impl From<char> for Tile {
     }
 }
 
+impl Tile {
+    fn style(dot: char) -> Cell {
+        let cell = Cell::new(dot);
+        match Self::from(dot) {
+            Self::Spring => cell.fg(Color::Green).bold(),
+            Self::Spike => cell.fg(Color::Red),
+            Self::Checkpoint => cell.fg(Color::Cyan).bold(),
+            Self::Nothing => Cell::TRANSPARENT,
+            _ => cell,
+        }
+    }
+}
+
 pub struct Stage {
     data: Vec<Vec<char>>,
+    sprite: Sprite,
+    backgrounds: Vec<ParallaxLayer>,
 }
 
 impl Stage {
+    /// Also picks up background layers from companion files named after the stage
+    /// with `.bg1`, `.bg2` and so on appended, nearest first, see `ParallaxLayer::load`.
     pub fn load(stage_file: &Path) -> std::io::Result<Self> {
-        let mut data = Vec::new();
-        let file = File::open(stage_file)?;
-        for line in BufReader::new(file).lines() {
-            data.push(line?.chars().collect());
+        let mut stage = Self::parse(&std::fs::read_to_string(stage_file)?);
+
+        for n in 1.. {
+            let mut path = stage_file.as_os_str().to_owned();
+            path.push(format!(".bg{n}"));
+            let path = Path::new(&path);
+            if !path.exists() {
+                break;
+            }
+            stage.backgrounds.push(ParallaxLayer::load(path)?);
+        }
+
+        Ok(stage)
+    }
+
+    /// A stage laid out in `text`, one row per line, without any backgrounds.
+    pub fn parse(text: &str) -> Self {
+        let data: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+
+        let sprite = data
+            .iter()
+            .map(|row| row.iter().map(|dot| Tile::style(*dot)).collect())
+            .collect();
+
+        Self {
+            data,
+            sprite,
+            backgrounds: Vec::new(),
         }
-        Ok(Self { data })
+    }
+
+    /// A hash of the layout that stays the same across builds, e.g. to tell
+    /// which stage a replay was recorded on.
+    pub fn checksum(&self) -> u64 {
+        // FNV-1a, over every tile and the end of every row
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for row in &self.data {
+            let dots = row.iter().chain(['\n'].iter());
+            for byte in dots.flat_map(|dot| u32::from(*dot).to_le_bytes()) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+        hash
+    }
+
+    /// Size of the stage in cells, measured from the origin. Rows can be ragged,
+    /// so this is the longest one.
+    pub fn extent(&self) -> ScreenPos {
+        let width = self.data.iter().map(Vec::len).max().unwrap_or(0);
+        ScreenPos::new(width as ScreenCoord, self.data.len() as ScreenCoord)
     }
 
     pub fn check_pos(&self, pos: Pos) -> Tile {
         let (x, y) = ScreenPos::from(pos).indices();
+        self.tile(x, y)
+    }
+
+    /// The tile in column `x` of row `y`.
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
         self.data
             .get(y)
             .and_then(|row| row.get(x))
@@ -52,6 +117,15 @@ impl Stage {
 
 impl Drawable for Stage {
     fn draw(&self, camera: &crate::engine::Camera, renderer: &mut crate::engine::Renderer) {
-        camera.paint_sprite(&self.data, Pos::ZERO, renderer);
+        // furthest first, so nearer layers cover it
+        for background in self.backgrounds.iter().rev() {
+            renderer.draw(background, camera);
+        }
+
+        camera.paint_sprite(&self.sprite, Pos::ZERO, renderer);
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Stage
     }
 }
@@ -1,26 +1,42 @@
-use std::time::Instant;
+use std::time::Duration;
 
-use crate::engine::Drawable;
+use crate::engine::{Align, Clock, Dimension, Drawable, Layer};
 
 pub struct Timer {
-    start_time: Instant,
+    elapsed: Duration,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Timer {
     pub fn new() -> Self {
         Self {
-            start_time: Instant::now(),
+            elapsed: Duration::ZERO,
         }
     }
+
+    pub fn update(&mut self, clock: &Clock) {
+        self.elapsed = clock.now();
+    }
 }
 
 impl Drawable for Timer {
-    fn draw(&self, _camera: &crate::engine::Camera, renderer: &mut crate::engine::Renderer) {
-        let time_string = format!("{:.0?}", self.start_time.elapsed());
-        let y = renderer.height() - 1;
-        let x_offset = renderer.width() - time_string.len() as u16;
-        for (i, dot) in time_string.chars().enumerate().take(time_string.len() - 1) {
-            renderer.paint(i as u16 + x_offset, y, dot);
-        }
+    fn draw(&self, camera: &crate::engine::Camera, renderer: &mut crate::engine::Renderer) {
+        let time_string = format!("{:.0?}", self.elapsed);
+        let x = camera.frame_pos.x as Dimension + camera.width;
+        renderer.print(
+            x,
+            camera.frame_pos.y as Dimension,
+            Align::Right,
+            &time_string,
+        );
+    }
+
+    fn layer(&self) -> Layer {
+        Layer::Hud
     }
 }
@@ -1,77 +1,244 @@
 #![allow(dead_code)]
 
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use crossterm::terminal;
 
-use engine::{Button, Camera, Drawable, Input, Logger, Pos, Renderer, ScreenPos};
-use game::{Bear, Border, Player, Stage, Timer, UPDATE_RATE};
-
+use args::Args;
+use engine::{
+    Bindings, BorderStyle, Button, Corner, Dimension, Drawable, GameLoop, Input, LogConfig,
+    LogConsole, LogFilter, Logger, Placement, Pos, Renderer, ScreenshotFormat, Split,
+    TerminalSession, Viewport, Viewports,
+};
+use game::{
+    Bear, DeathCounter, FollowCam, FollowConfig, MapMode, Minimap, Player, Stage, Timer,
+    MIN_COLUMNS, MIN_ROWS, RENDER_RATE, UPDATE_INTERVAL,
+};
+
+mod args;
 mod engine;
 mod game;
 
+/// A fresh file name in the working directory, e.g. for recordings and screenshots.
+/// Gets a counter appended if a file by that name is already there, so nothing
+/// is overwritten.
+fn timestamped_path(extension: &str) -> PathBuf {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis());
+    let mut path = PathBuf::from(format!("splat-{millis}.{extension}"));
+    let mut count = 1;
+    while path.exists() {
+        path = PathBuf::from(format!("splat-{millis}-{count}.{extension}"));
+        count += 1;
+    }
+    path
+}
+
 fn main() -> std::io::Result<()> {
-    let size = terminal::window_size()?;
-    let width = size.columns;
-    let height = size.rows - 10;
-
-    let logger = Logger::setup().unwrap();
-    let mut renderer = Renderer::new(width, height, Some(logger))?;
-
-    // leave room for border and status bar
-    let mut camera = Camera {
-        pos: Pos::ZERO,
-        frame_pos: ScreenPos::new(1, 1),
-        width: width - 2,
-        height: height - 3,
+    let args = Args::parse()?;
+
+    let bindings = match &args.bindings {
+        Some(path) => Bindings::load(path)?,
+        None => Bindings::from_env()?,
     };
+    if args.list_bindings {
+        print!("{bindings}");
+        for problem in bindings.validate() {
+            // as a comment, so the output still loads as a bindings file
+            println!("# warning: {problem}");
+        }
+        return Ok(());
+    }
+
+    let size = terminal::window_size()?;
+    let (mut width, mut height) = (size.columns, size.rows);
 
-    let mut input = Input::new()?;
+    let mut log_config = LogConfig::from_env()?;
+    if let Some(spec) = &args.log {
+        log_config.filter = LogFilter::parse(spec)?;
+    }
+    if let Some(path) = &args.log_file {
+        log_config.file = Some(path.clone());
+    }
+    let logger = Logger::setup_with(log_config)?;
+    let session = TerminalSession::start()?;
+    let mut renderer = Renderer::new(width, height)?;
+    if let Some(path) = &args.record {
+        renderer.start_recording(path)?;
+    }
+
+    let mut viewports = Viewports::new(Split::Horizontal);
+    let hud_view = viewports.add(Viewport::new(Placement::Bottom(1)));
+    let game_view = viewports.add(Viewport::new(Placement::Fill).border(BorderStyle::Single));
+    let checkpoint_view = viewports.add(
+        Viewport::new(Placement::Inset {
+            corner: Corner::TopRight,
+            width: 26,
+            height: 9,
+        })
+        .border(BorderStyle::Rounded)
+        .title("respawning"),
+    );
+    let map_corner_view = viewports.add(
+        Viewport::new(Placement::Inset {
+            corner: Corner::TopLeft,
+            width: 32,
+            height: 12,
+        })
+        .border(BorderStyle::Rounded)
+        .title("map"),
+    );
+    let map_full_view = viewports.add(
+        Viewport::new(Placement::Inset {
+            corner: Corner::TopLeft,
+            width: Dimension::MAX,
+            height: Dimension::MAX,
+        })
+        .border(BorderStyle::Double)
+        .title("map"),
+    );
+    viewports.layout(width, height);
+
+    for problem in bindings.validate() {
+        log::warn!("key bindings: {problem}");
+    }
     let stage = Stage::load(Path::new("game.stage"))?;
+    let mut input = Input::with_bindings(bindings)?;
+    if let Some(path) = &args.record_input {
+        input.record_to(path, UPDATE_INTERVAL, stage.checksum())?;
+    }
+    if let Some(path) = &args.replay {
+        input.replay_from(path, UPDATE_INTERVAL, stage.checksum())?;
+    }
 
-    let border = Border;
-    let timer = Timer::new();
+    let mut console = LogConsole::new(logger);
 
     let mut player = Player::new(Pos::new(13.0, 102.0));
+    let mut follow_cam = FollowCam::new(FollowConfig {
+        bounds: Some(stage.extent()),
+        ..FollowConfig::default()
+    });
     let mut left_bear = Bear::new(Pos::new(373.0, 756.0));
     let mut right_bear = Bear::new(Pos::new(450.0, 756.0));
 
-    // use spin_sleep since native sleep is often too slow / low res
-    let mut loop_helper = spin_sleep::LoopHelper::builder()
-        .report_interval_s(1.0)
-        .build_with_target_rate(UPDATE_RATE);
-    let mut actual_rate = 0.0;
-
-    loop {
-        loop_helper.loop_start();
-
-        if let Some(rate) = loop_helper.report_rate() {
-            actual_rate = rate;
+    let mut timer = Timer::new();
+    let mut death_counter = DeathCounter::new();
+    let mut minimap = Minimap::new(&stage);
+    let mut game_loop = GameLoop::new(UPDATE_INTERVAL, RENDER_RATE);
+
+    'game: loop {
+        game_loop.tick();
+
+        while let Some(clock) = game_loop.step() {
+            // a hit-stop holds the world still and leaves the input unread, so
+            // presses made meanwhile take effect once it's over instead of getting lost
+            let effects = &mut viewports.camera_mut(game_view).effects;
+            if effects.frozen() {
+                effects.update(&clock);
+                timer.update(&clock);
+                player.hold();
+                follow_cam.hold();
+                continue;
+            }
+
+            input.update(viewports.camera(game_view))?;
+            if session.terminated() || input.pressed_this_frame(Button::Quit) {
+                break 'game;
+            }
+
+            if input.pressed_this_frame(Button::Screenshot) {
+                for format in ScreenshotFormat::ALL {
+                    let path = timestamped_path(format.extension());
+                    match renderer.screenshot(&path) {
+                        Ok(()) => log::info!("saved screenshot to {}", path.display()),
+                        Err(err) => {
+                            log::error!("can't save screenshot to {}: {err}", path.display())
+                        }
+                    }
+                }
+            }
+
+            if input.pressed_this_frame(Button::Record) {
+                // a recording is optional, so failing one shouldn't end the game
+                if renderer.recording() {
+                    match renderer.stop_recording() {
+                        Ok(()) => log::info!("stopped recording"),
+                        Err(err) => log::error!("failed to finish recording: {err}"),
+                    }
+                } else {
+                    let path = timestamped_path("cast");
+                    match renderer.start_recording(&path) {
+                        Ok(()) => log::info!("recording to {}", path.display()),
+                        Err(err) => log::error!("can't record to {}: {err}", path.display()),
+                    }
+                }
+            }
+
+            if let Some(size) = input.resized() {
+                (width, height) = size;
+                renderer.resize(width, height);
+                viewports.layout(width, height);
+            }
+
+            // a small terminal pauses the game, except for replays, which have to
+            // step the same way however big the terminal is. Paused steps are left
+            // out of recordings for the same reason
+            if (width < MIN_COLUMNS || height < MIN_ROWS) && !input.replaying() {
+                game_loop.skip_step();
+                input.skip_step();
+                continue;
+            }
+
+            console.update(&input, height);
+            timer.update(&clock);
+            minimap.update(&input, &player, viewports.camera(game_view));
+
+            let effects = &mut viewports.camera_mut(game_view).effects;
+            effects.update(&clock);
+            player.update(&input, &stage, &clock, effects);
+            death_counter.update(&player);
+            follow_cam.update(&player, &input, &clock);
+            left_bear.update(&clock);
+            right_bear.update(&clock);
         }
-        dbg!(actual_rate);
 
-        input.update(&camera)?;
-        if input.pressed_this_frame(Button::Quit) {
-            break;
+        if let Some(alpha) = game_loop.render() {
+            renderer.clear();
+
+            if width < MIN_COLUMNS || height < MIN_ROWS {
+                renderer.paint_notice(&format!(
+                    "please enlarge the terminal to at least {MIN_COLUMNS}x{MIN_ROWS}"
+                ));
+            } else {
+                renderer.set_alpha(alpha);
+                follow_cam.apply(viewports.camera_mut(game_view), &player, alpha);
+                viewports.get_mut(checkpoint_view).visible = player.dying();
+                viewports
+                    .camera_mut(checkpoint_view)
+                    .center_on(player.checkpoint());
+
+                let world: [&dyn Drawable; 4] = [&stage, &player, &left_bear, &right_bear];
+                viewports.draw(game_view, &world, &mut renderer);
+                viewports.draw(checkpoint_view, &world, &mut renderer);
+                let hud: [&dyn Drawable; 2] = [&death_counter, &timer];
+                viewports.draw(hud_view, &hud, &mut renderer);
+
+                viewports.get_mut(map_corner_view).visible = minimap.mode() == MapMode::Corner;
+                viewports.get_mut(map_full_view).visible = minimap.mode() == MapMode::Full;
+                viewports.draw(map_corner_view, &[&minimap], &mut renderer);
+                viewports.draw(map_full_view, &[&minimap], &mut renderer);
+
+                renderer.draw(&console, viewports.camera(game_view));
+            }
+
+            renderer.render()?;
         }
 
-        player.update(&input, &stage);
-        left_bear.update();
-        right_bear.update();
-        camera.update(&player);
-
-        renderer.clear();
-
-        stage.draw(&camera, &mut renderer);
-        player.draw(&camera, &mut renderer);
-        left_bear.draw(&camera, &mut renderer);
-        right_bear.draw(&camera, &mut renderer);
-        border.draw(&camera, &mut renderer);
-        timer.draw(&camera, &mut renderer);
-
-        renderer.render()?;
-
-        loop_helper.loop_sleep();
+        game_loop.sleep();
     }
 
     Ok(())